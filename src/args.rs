@@ -1,52 +1,151 @@
 use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
-#[derive(Default, Clone)]
-pub enum Platform {
-    Linux,
-    OsX,
-    Windows,
-    Android,
-    SunOs,
-    #[default]
-    Common,
+use crate::consts::UNTRANSLATED_MARKER;
+use crate::error::Error;
+
+/// Parses the marker for untranslated lines, which would match every line if it was blank.
+fn parse_marker(s: &str) -> Result<String, String> {
+    if s.trim().is_empty() {
+        Err("the marker must not be empty".to_string())
+    } else {
+        Ok(s.to_string())
+    }
+}
+
+/// A platform, i.e. the name of a directory in `pages/`.
+///
+/// Platforms are not hard-coded, so that platforms added upstream work without a new release.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Platform(String);
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self("common".to_string())
+    }
 }
 
 impl FromStr for Platform {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "linux" => Ok(Self::Linux),
-            "macos" | "osx" => Ok(Self::OsX),
-            "windows" => Ok(Self::Windows),
-            "android" => Ok(Self::Android),
-            "sunos" => Ok(Self::SunOs),
-            "common" => Ok(Self::Common),
-            _ => Err(format!("invalid platform '{s}' (possible values: linux, macos, osx, windows, android, sunos, common)'"))
+        let s = s.to_lowercase();
+
+        if s.is_empty() || s.contains(['/', '\\', '.']) {
+            return Err(format!("invalid platform name '{s}'"));
         }
+
+        // Aliases for directory names.
+        Ok(Self(match s.as_str() {
+            "macos" => "osx".to_string(),
+            _ => s,
+        }))
     }
 }
 
 impl Display for Platform {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Linux => "linux",
-                Self::OsX => "osx",
-                Self::Windows => "windows",
-                Self::Android => "android",
-                Self::SunOs => "sunos",
-                Self::Common => "common",
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Platform {
+    /// Returns all platforms that exist in the local clone of tldr, sorted.
+    pub fn all(repo_path: &Path) -> Result<Vec<Self>, Error> {
+        let mut platforms = vec![];
+
+        for entry in fs::read_dir(repo_path.join("pages"))? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                platforms.push(Self(entry.file_name().to_string_lossy().into_owned()));
             }
-        )
+        }
+
+        platforms.sort();
+        Ok(platforms)
+    }
+
+    /// Returns an error listing the existing platforms if this platform does not exist.
+    pub fn ensure_exists(&self, repo_path: &Path) -> Result<(), Error> {
+        if repo_path.join("pages").join(&self.0).is_dir() {
+            return Ok(());
+        }
+
+        let platforms: Vec<String> = Self::all(repo_path)?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        Err(Error::Msg(format!(
+            "platform '{self}' does not exist (possible values: {})",
+            platforms.join(", ")
+        )))
     }
 }
 
+/// Which lines of pages to search. If none are selected, all lines are searched.
+#[derive(Args, Clone, Copy)]
+pub struct SearchScope {
+    /// Search titles.
+    #[arg(long)]
+    pub titles: bool,
+
+    /// Search descriptions (of pages and examples).
+    #[arg(long)]
+    pub descriptions: bool,
+
+    /// Search command lines.
+    #[arg(long)]
+    pub commands: bool,
+}
+
+impl SearchScope {
+    pub fn includes(self, line: &str) -> bool {
+        let all = !(self.titles || self.descriptions || self.commands);
+        match line.chars().next() {
+            Some('#') => all || self.titles,
+            Some('>' | '-') => all || self.descriptions,
+            Some('`') => all || self.commands,
+            _ => all,
+        }
+    }
+}
+
+/// Filters for 'tlt list'.
+#[derive(Args, Clone)]
+pub struct ListFilters {
+    /// Only list alias pages.
+    #[arg(long)]
+    pub aliases: bool,
+
+    /// Only list pages that have not been translated to LANGUAGE.
+    #[arg(long, value_name = "LANGUAGE")]
+    pub untranslated: Option<String>,
+
+    /// Only list pages that exist on multiple platforms.
+    #[arg(long)]
+    pub multi_platform: bool,
+
+    /// Only list pages added or modified on the current branch.
+    #[arg(long)]
+    pub modified: bool,
+}
+
+/// Output format of 'tlt list'.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ListFormat {
+    /// Page names only.
+    #[default]
+    Plain,
+    /// Path, size and date of the last commit.
+    Long,
+    Json,
+}
+
 #[derive(Parser)]
 #[command(
     arg_required_else_help = true,
@@ -62,7 +161,7 @@ impl Display for Platform {
 \x1b[1mtlt\x1b[0m <SHELL_COMMAND> [ARGS]..."
 )]
 pub struct Cli {
-    /// Specify the platform to use [linux, macos/osx, windows, android, sunos, common].
+    /// Specify the platform to use (any directory in 'pages/', e.g. linux, osx/macos, windows, common).
     #[arg(short, long)]
     pub platform: Option<Platform>,
 
@@ -70,10 +169,50 @@ pub struct Cli {
     #[arg(short = 'L', long, value_name = "LANGUAGE")]
     pub language: Option<String>,
 
+    /// Use the tldr repository at PATH (overrides $TLT_REPO and the config file).
+    #[allow(clippy::doc_markdown)]
+    #[arg(long, value_name = "PATH")]
+    pub repo: Option<PathBuf>,
+
+    /// Use the profile NAME from the config file instead of the active one.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+#[derive(Subcommand, Clone)]
+pub enum ProfileCommand {
+    /// List profiles, marking the active one with '*'.
+    #[command(visible_alias = "ls")]
+    List,
+
+    /// Add a profile.
+    Add {
+        name: String,
+
+        /// The fork of the profile (owner/name).
+        #[arg(long, value_name = "OWNER/NAME")]
+        fork: String,
+
+        /// The location of the repository [default: a separate clone in the cache directory].
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// The default language.
+        #[arg(long, value_name = "LANGUAGE")]
+        language: Option<String>,
+    },
+
+    /// Make a profile active ('default' to use the 'repository' and 'defaults' sections).
+    Use { name: String },
+
+    /// Remove a profile.
+    #[command(visible_alias = "rm")]
+    Remove { name: String },
+}
+
 #[derive(Subcommand, Clone)]
 pub enum Commands {
     /// Run a command inside the tldr repository.
@@ -81,7 +220,26 @@ pub enum Commands {
     Run(Vec<String>),
 
     /// Create a new page.
-    Add { page: String, doc_url: String },
+    Add {
+        page: String,
+        doc_url: String,
+
+        /// Fill in the page from the output of `COMMAND --help`, or from stdin if COMMAND is '-'.
+        #[arg(long, value_name = "COMMAND", group = "scaffold")]
+        from_help: Option<String>,
+
+        /// Like '--from-help', using PAGE as the command.
+        #[arg(long, group = "scaffold")]
+        from_page_help: bool,
+
+        /// Fill in the page from the man page of COMMAND, or from a roff file if COMMAND is a path.
+        #[arg(long, value_name = "COMMAND", group = "scaffold")]
+        from_man: Option<String>,
+
+        /// Like '--from-man', using PAGE as the command.
+        #[arg(long, group = "scaffold")]
+        from_page_man: bool,
+    },
 
     /// Create an alias page.
     #[command(visible_alias = "al")]
@@ -89,7 +247,13 @@ pub enum Commands {
 
     /// Translate a page.
     #[command(visible_alias = "t")]
-    Translate { page: String },
+    Translate {
+        page: String,
+
+        /// Prefix descriptions that need to be translated with MARKER.
+        #[arg(long, default_value = UNTRANSLATED_MARKER, value_parser = parse_marker)]
+        marker: String,
+    },
 
     /// Edit a page.
     #[command(visible_alias = "e")]
@@ -98,6 +262,30 @@ pub enum Commands {
         page: Vec<String>,
     },
 
+    /// Remove a page and all its translations (or only the one given by '--language').
+    #[command(visible_alias = "rm")]
+    Remove {
+        #[arg(required = true)]
+        page: Vec<String>,
+    },
+
+    /// Rename a page on all platforms and in all languages, updating references to it.
+    Rename { old: String, new: String },
+
+    /// Move a page and all its translations to another platform.
+    #[command(visible_alias = "mv")]
+    Move {
+        page: String,
+
+        /// The platform to move the page from.
+        #[arg(long)]
+        from: Platform,
+
+        /// The platform to move the page to.
+        #[arg(long)]
+        to: Platform,
+    },
+
     /// Update your fork's main branch from upstream.
     #[command(visible_alias = "u")]
     Update,
@@ -106,19 +294,128 @@ pub enum Commands {
     #[command(visible_alias = "b")]
     Branch { branch: Option<String> },
 
-    /// Run 'tldr --render' on a page.
+    /// Render a page.
     #[command(visible_alias = "v")]
     View {
         #[arg(required = true)]
         page: Vec<String>,
+
+        /// Run 'tldr --render' instead of using the built-in renderer.
+        #[arg(long)]
+        external: bool,
+    },
+
+    /// Check pages for style errors (all pages changed on the current branch by default).
+    #[command(visible_alias = "l")]
+    Lint { pages: Vec<String> },
+
+    /// Rewrite a page in the canonical format (all pages changed on the current branch by default).
+    #[command(visible_alias = "f")]
+    Format {
+        page: Vec<String>,
+
+        /// Only report pages that are not formatted, without changing them.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Show a translation side by side with the English page.
+    #[command(visible_alias = "c")]
+    Compare {
+        #[arg(required = true)]
+        page: Vec<String>,
+    },
+
+    /// Report translations that differ from or are older than the English page.
+    #[command(visible_alias = "o")]
+    Outdated,
+
+    /// Find lines that have not been translated (in all translations by default).
+    #[command(visible_alias = "ut")]
+    Untranslated {
+        pages: Vec<String>,
+
+        /// Also report lines containing MARKER, as left by 'translate'.
+        #[arg(long, default_value = UNTRANSLATED_MARKER, value_parser = parse_marker)]
+        marker: String,
+    },
+
+    /// Search pages using a regular expression (on all platforms by default).
+    #[command(visible_alias = "s")]
+    Search {
+        pattern: String,
+
+        /// Match case-insensitively.
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// Search pages in all languages.
+        #[arg(short, long)]
+        all_languages: bool,
+
+        #[command(flatten)]
+        scope: SearchScope,
+    },
+
+    /// List pages (on all platforms by default).
+    #[command(visible_alias = "ls")]
+    List {
+        #[command(flatten)]
+        filters: ListFilters,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: ListFormat,
+    },
+
+    /// Show translation coverage per language and platform.
+    Stats {
+        /// List English pages that have not been translated.
+        #[arg(long)]
+        missing: bool,
+
+        /// Print the statistics as JSON.
+        #[arg(long)]
+        json: bool,
     },
 
     /// Create a pull request to tldr-pages from the current branch.
     #[command(visible_alias = "pr")]
     PullRequest,
 
+    /// Manage profiles for multiple forks.
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+
+    /// Check that the git remotes match the config file.
+    Remotes {
+        /// Add missing remotes and fix their URLs.
+        #[arg(long)]
+        repair: bool,
+    },
+
     /// Recreate the config file.
-    Setup,
+    #[command(long_about = "Recreate the config file. \
+        An existing clone of tldr given by --path, --repo or $TLT_REPO is reused.")]
+    Setup {
+        /// Set the fork non-interactively (owner/name).
+        #[arg(long, value_name = "OWNER/NAME")]
+        fork: Option<String>,
+
+        /// Set the location of the repository, reusing an existing clone of tldr.
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Clone the fork using https instead of ssh.
+        #[arg(long)]
+        https: bool,
+
+        /// Delete the old repository without asking.
+        #[arg(short, long)]
+        yes: bool,
+    },
 
     /// Remove the repository and the config file.
     Destroy,
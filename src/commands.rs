@@ -1,17 +1,32 @@
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
-use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
 
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use terminal_size::{terminal_size, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use yansi::{Color, Paint};
 
-use crate::args::Platform;
+use crate::args::{ListFilters, ListFormat, Platform, ProfileCommand, SearchScope};
+use crate::config::{check_fork, check_language, Config, DocHostsConfig, Profile, DEFAULT_PROFILE};
 use crate::consts::{ALIAS_PAGES, MORE_INFORMATION};
+use crate::doc_url::{self, DocHosts};
 use crate::error::{Error, Result};
-use crate::repo::Repository;
+use crate::lint;
+use crate::page::Page;
+use crate::render::render;
+use crate::repo::{RemoteStatus, Repository};
+use crate::scaffold;
+use crate::stats;
+use crate::translation::{self, Alignment};
 use crate::util::{
-    browser, cmd, edit_page, existing_page_path, get_output, infoln, is_success, page_path, spawn,
-    warnln,
+    absolute, all_pages, browser, changed_pages, cmd, confirm, edit_page, existing_page_path,
+    find_page, get_output, infoln, is_success, language_dir, last_commit_dates, list_languages,
+    list_pages, modified_copies, page_copies, page_language, page_not_found, page_path, spawn,
+    untracked_copies, warnln, PageCopy,
 };
 
 pub fn update() -> Result<()> {
@@ -42,36 +57,177 @@ pub fn update() -> Result<()> {
     Ok(())
 }
 
-pub fn view(repo_path: &Path, page: &[String], language: &str, platform: &Platform) -> Result<()> {
+pub fn view(
+    repo_path: &Path,
+    page: &[String],
+    external: bool,
+    renderer: Option<&str>,
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
     let page = page.join("-").to_lowercase();
-    let path = existing_page_path(repo_path, platform, language, &page)?;
+    let (_, path) = find_page(repo_path, platform, language, &page)?;
 
-    spawn(&mut cmd!("tldr", "--render", path))
+    if external {
+        spawn(&mut cmd!("tldr", "--render", path))
+    } else if let Some(renderer) = renderer {
+        let mut words = renderer.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| Error::Msg("the renderer must not be empty".to_string()))?;
+        spawn(cmd!(program).args(words).arg(path))
+    } else {
+        Ok(render(&Page::read(&path)?)?)
+    }
 }
 
-pub fn destroy(repo_path: &Path) -> Result<()> {
-    warnln!(
-        "you {} be able to recover changes that have not been pushed to GitHub",
-        Paint::new("WILL NOT").fg(Color::Red).bold()
-    );
+pub fn remotes(repo: &Repository, repair: bool) -> Result<()> {
+    let mut mismatched = 0;
+
+    for (name, url) in repo.remotes() {
+        let status = repo.remote_status(name, &url)?;
+        match &status {
+            RemoteStatus::Ok => {
+                infoln!("'{name}' points to '{url}'");
+                continue;
+            }
+            RemoteStatus::Missing => warnln!("'{name}' does not exist, expected '{url}'"),
+            RemoteStatus::Mismatch(actual) => {
+                warnln!("'{name}' points to '{actual}', expected '{url}'");
+            }
+        }
+
+        if repair {
+            repo.set_remote(name, &url, &status)?;
+        } else {
+            mismatched += 1;
+        }
+    }
+
+    if mismatched > 0 {
+        return Err(Error::Msg(format!(
+            "{mismatched} remote(s) do not match the config file. Run 'tlt remotes --repair' to fix them."
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn profile(command: ProfileCommand) -> Result<()> {
+    let mut config = Config::get()?.ok_or_else(|| {
+        Error::Msg("the config file does not exist. Run 'tlt setup' first.".to_string())
+    })?;
+
+    match command {
+        ProfileCommand::List => {
+            let mut stdout = io::stdout().lock();
+            let active = config.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+            let mark = |name: &str| if name == active { '*' } else { ' ' };
+
+            writeln!(
+                stdout,
+                "{} {DEFAULT_PROFILE}: {}",
+                mark(DEFAULT_PROFILE),
+                config.repository.fork
+            )?;
+            for (name, profile) in &config.profiles {
+                write!(stdout, "{} {name}: {}", mark(name), profile.fork)?;
+                if let Some(path) = &profile.path {
+                    write!(stdout, " ({})", path.display())?;
+                }
+                if let Some(language) = &profile.language {
+                    write!(stdout, " [{language}]")?;
+                }
+                writeln!(stdout)?;
+            }
+            return Ok(());
+        }
+        ProfileCommand::Add {
+            name,
+            fork,
+            path,
+            language,
+        } => {
+            if name == DEFAULT_PROFILE || config.profiles.contains_key(&name) {
+                return Err(Error::Msg(format!("profile '{name}' already exists")));
+            }
+            let fork = check_fork(&fork).map_err(Error::Msg)?;
+            if let Some(language) = &language {
+                check_language(language).map_err(Error::Msg)?;
+            }
+            let path = path.as_deref().map(absolute).transpose()?;
+
+            infoln!("adding profile '{name}'. Run 'tlt profile use {name}' to make it active.");
+            config.profiles.insert(
+                name,
+                Profile {
+                    fork,
+                    path,
+                    language,
+                },
+            );
+        }
+        ProfileCommand::Use { name } => {
+            if name == DEFAULT_PROFILE {
+                config.profile = None;
+            } else {
+                config.profile(&name).map_err(Error::Msg)?;
+                config.profile = Some(name.clone());
+            }
+            infoln!("using profile '{name}'");
+        }
+        ProfileCommand::Remove { name } => {
+            config.profile(&name).map_err(Error::Msg)?;
+            config.profiles.remove(&name);
+
+            infoln!("removing profile '{name}'");
+            if config.profile.as_ref() == Some(&name) {
+                infoln!("using profile '{DEFAULT_PROFILE}'");
+                config.profile = None;
+            }
+        }
+    }
+
+    config.write()
+}
+
+/// Removes the config file and the repository, unless the repository was not cloned by tlt.
+pub fn destroy(repo_path: &Path, managed: bool) -> Result<()> {
+    if managed {
+        warnln!(
+            "you {} be able to recover changes that have not been pushed to GitHub",
+            Paint::new("WILL NOT").fg(Color::Red).bold()
+        );
+    }
     writeln!(io::stdout(), "Press Enter to confirm. ")?;
     io::stdout().flush()?;
     io::stdin().read_line(&mut String::new())?;
 
-    infoln!("removing '{}'...", repo_path.display());
-    fs::remove_dir_all(repo_path)?;
+    if managed {
+        infoln!("removing '{}'...", repo_path.display());
+        fs::remove_dir_all(repo_path)?;
+    } else {
+        infoln!(
+            "not removing '{}', because it was not cloned by tlt",
+            repo_path.display()
+        );
+    }
 
-    let config_dir = Repository::locate_config();
+    let config_dir = Config::locate();
     infoln!("removing '{}'...", config_dir.parent().unwrap().display());
     fs::remove_dir_all(config_dir.parent().unwrap())?;
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn add(
     repo_path: &Path,
     page: &str,
     doc_url: &str,
+    doc_hosts: &DocHostsConfig,
+    from_help: Option<&str>,
+    from_man: Option<&str>,
     language: &str,
     platform: &Platform,
 ) -> Result<()> {
@@ -84,17 +240,51 @@ pub fn add(
         )));
     }
 
+    let normalized_url = doc_url::check(doc_url, platform, &DocHosts::new(doc_hosts))?;
+    if normalized_url != doc_url {
+        infoln!("normalized the documentation URL to '{normalized_url}'");
+    }
+    let doc_url = normalized_url.as_str();
+
+    let scaffolded = if let Some(command) = from_help {
+        let help = scaffold::help_text(command)?;
+        Some((
+            scaffold::help_description(&help),
+            scaffold::parse_options(&help),
+        ))
+    } else if let Some(command) = from_man {
+        let man = scaffold::man_text(command)?;
+        Some((scaffold::man_description(&man), scaffold::man_options(&man)))
+    } else {
+        None
+    };
+
     infoln!("creating page '{language}/{platform}/{page}'...");
-    write!(
-        File::create(&page_path)?,
-        "# {page}\n\n\
-            >\n>\n> {}: <{doc_url}>.\n\n-\n\n``\n",
-        MORE_INFORMATION[language]
-    )?;
+    if let Some((description, options)) = scaffolded {
+        let new_page = scaffold::page(&page, description, &options, doc_url, language);
+        write!(File::create(&page_path)?, "{new_page}")?;
+    } else {
+        write!(
+            File::create(&page_path)?,
+            "# {page}\n\n\
+                >\n>\n> {}: <{doc_url}>.\n\n-\n\n``\n",
+            MORE_INFORMATION[language]
+        )?;
+    }
 
     edit_page(&page_path)
 }
 
+/// Returns the contents of an alias page in `language`, if the alias page has been translated.
+fn alias_page(language: &str, page: &str, alias_of: &str) -> Option<String> {
+    Some(
+        ALIAS_PAGES
+            .get(language)?
+            .replacen("example", page, 1)
+            .replace("example", alias_of),
+    )
+}
+
 pub fn alias(
     repo_path: &Path,
     new_page: &str,
@@ -118,9 +308,7 @@ pub fn alias(
         )));
     }
 
-    let alias: String = ALIAS_PAGES[language]
-        .replacen("example", &new_page, 1)
-        .replace("example", &alias_of);
+    let alias = alias_page(language, &new_page, &alias_of).unwrap();
 
     infoln!("creating alias ({language}/{platform}): '{new_page}' => '{alias_of}'");
     fs::create_dir_all(new_page_path.parent().unwrap())?;
@@ -129,13 +317,19 @@ pub fn alias(
     edit_page(&new_page_path)
 }
 
-pub fn edit(repo_path: &Path, page: &[String], language: &str, platform: &Platform) -> Result<()> {
-    edit_page(&existing_page_path(
+pub fn edit(
+    repo_path: &Path,
+    page: &[String],
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    let (_, path) = find_page(
         repo_path,
         platform,
         language,
         &page.join("-").to_lowercase(),
-    )?)
+    )?;
+    edit_page(&path)
 }
 
 pub fn branch(branch: Option<String>) -> Result<()> {
@@ -158,16 +352,23 @@ pub fn branch(branch: Option<String>) -> Result<()> {
     }
 }
 
-pub fn translate(repo_path: &Path, page: &str, language: &str, platform: &Platform) -> Result<()> {
+pub fn translate(
+    repo_path: &Path,
+    page: &str,
+    marker: &str,
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
     let page = page.to_lowercase();
 
     if language == "en" {
         warnln!("no language specified, running 'edit' instead");
-        edit_page(&existing_page_path(repo_path, platform, language, &page)?)?;
-        return Ok(());
+        return edit_page(&find_page(repo_path, platform, language, &page)?.1);
     }
 
-    let page_path = page_path(repo_path, platform, language, &page);
+    // The translation is created on the platform of the English page.
+    let (platform, en_page_path) = find_page(repo_path, platform, "en", &page)?;
+    let page_path = page_path(repo_path, &platform, language, &page);
 
     if page_path.is_file() {
         infoln!("this translation already exists, running 'edit' instead");
@@ -175,23 +376,20 @@ pub fn translate(repo_path: &Path, page: &str, language: &str, platform: &Platfo
         return Ok(());
     }
 
-    let en_page_path = existing_page_path(repo_path, platform, "en", &page).map_err(|_| {
-        Error::Msg(format!(
-            "page 'en/{platform}/{page}' does not exist, create it first"
-        ))
-    })?;
-
-    let page_str =
-        fs::read_to_string(en_page_path)?.replace("More information", MORE_INFORMATION[language]);
+    let english = Page::read(&en_page_path)?;
+    let translation = english
+        .alias_of()
+        .and_then(|alias_of| alias_page(language, &english.title, alias_of))
+        .unwrap_or_else(|| translation::scaffold(&english, language, marker).to_string());
 
     infoln!("creating page '{language}/{platform}/{page}'...");
     fs::create_dir_all(page_path.parent().unwrap())?;
-    write!(File::create(&page_path)?, "{page_str}")?;
+    write!(File::create(&page_path)?, "{translation}")?;
 
     edit_page(&page_path)
 }
 
-pub fn pull_request(fork: &str) -> Result<()> {
+pub fn pull_request(fork_web_url: &str) -> Result<()> {
     let branch = get_output(&mut cmd!("git", "branch", "--show-current"))?;
 
     if branch == "main" {
@@ -213,7 +411,841 @@ pub fn pull_request(fork: &str) -> Result<()> {
         spawn(&mut cmd!("git", "push", "-u", "origin", &branch))?;
     }
 
-    browser(&format!("https://github.com/{fork}/pull/new/{branch}"))?;
+    browser(&format!("{fork_web_url}/pull/new/{branch}"))?;
+
+    Ok(())
+}
+
+/// Resolves `pages`, or returns all pages changed on the current branch if `pages` is empty.
+fn pages_or_changed(
+    repo_path: &Path,
+    pages: &[String],
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<Vec<PathBuf>> {
+    if pages.is_empty() {
+        let changed = changed_pages(repo_path)?;
+        if changed.is_empty() {
+            infoln!("no pages have been changed on the current branch");
+        }
+        return Ok(changed);
+    }
+
+    pages
+        .iter()
+        .map(|page| Ok(find_page(repo_path, platform, language, &page.to_lowercase())?.1))
+        .collect()
+}
+
+pub fn lint(
+    repo_path: &Path,
+    pages: &[String],
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    let mut problems = 0;
+
+    for path in pages_or_changed(repo_path, pages, language, platform)? {
+        let name = path.file_stem().unwrap().to_string_lossy();
+        let diagnostics = lint::lint(&fs::read_to_string(&path)?, &name, &page_language(&path));
+        let display_path = path.strip_prefix(repo_path).unwrap_or(&path).display();
+
+        for diagnostic in &diagnostics {
+            writeln!(
+                io::stdout(),
+                "{display_path}:{}: {} {}",
+                diagnostic.line,
+                Paint::new(diagnostic.rule).fg(Color::Red).bold(),
+                diagnostic.rule.message()
+            )?;
+        }
+        problems += diagnostics.len();
+    }
+
+    if problems == 0 {
+        infoln!("no problems found");
+        Ok(())
+    } else {
+        Err(Error::Msg(format!("found {problems} problem(s)")))
+    }
+}
 
+pub fn format(
+    repo_path: &Path,
+    page: &[String],
+    check: bool,
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    let pages = if page.is_empty() {
+        vec![]
+    } else {
+        vec![page.join("-")]
+    };
+    let mut unformatted = 0;
+
+    for path in pages_or_changed(repo_path, &pages, language, platform)? {
+        let contents = fs::read_to_string(&path)?;
+        let mut page = Page::read(&path)?;
+        page.normalize();
+
+        let formatted = page.to_string();
+        if formatted == contents {
+            continue;
+        }
+
+        let display_path = path.strip_prefix(repo_path).unwrap_or(&path).display();
+        if check {
+            writeln!(io::stdout(), "{display_path}: not formatted")?;
+            unformatted += 1;
+        } else {
+            infoln!("formatting '{display_path}'...");
+            fs::write(&path, formatted)?;
+        }
+    }
+
+    if unformatted == 0 {
+        Ok(())
+    } else {
+        Err(Error::Msg(format!("{unformatted} page(s) need formatting")))
+    }
+}
+
+pub fn outdated(repo_path: &Path, language: &str) -> Result<()> {
+    if language == "en" {
+        return Err(Error::Msg(
+            "specify the language of translations to check using '--language'".to_string(),
+        ));
+    }
+
+    let en_dir = PathBuf::from("pages");
+    let lang_dir = PathBuf::from(format!("pages.{language}"));
+    let dates = last_commit_dates(&[en_dir.clone(), lang_dir.clone()])?;
+    let mut outdated = 0;
+
+    for (platform, page) in list_pages(repo_path, language)? {
+        let file = PathBuf::from(&platform).join(format!("{page}.md"));
+        let en_file = en_dir.join(&file);
+        let tr_file = lang_dir.join(&file);
+
+        let mut reasons = if en_file.is_file() {
+            match (Page::read(&en_file), Page::read(&tr_file)) {
+                (Ok(en), Ok(tr)) => translation::differences(&en, &tr)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                (Err(Error::Msg(e)), _) | (_, Err(Error::Msg(e))) => vec![e],
+                (Err(e), _) | (_, Err(e)) => return Err(e),
+            }
+        } else {
+            vec!["the English page does not exist".to_string()]
+        };
+
+        let en_date = dates.get(&en_file);
+        let tr_date = dates.get(&tr_file);
+        if let (Some((en_time, _)), Some((tr_time, _))) = (en_date, tr_date) {
+            if en_time > tr_time && reasons.is_empty() {
+                reasons.push("the English page has changed since".to_string());
+            }
+        }
+
+        if reasons.is_empty() {
+            continue;
+        }
+        outdated += 1;
+
+        let date = |d: Option<&(u64, String)>| {
+            d.map_or("uncommitted".to_string(), |(_, date)| date.clone())
+        };
+        writeln!(
+            io::stdout(),
+            "{}: {} (translated: {}, English: {})",
+            Paint::new(format!("{platform}/{page}")).bold(),
+            reasons.join("; "),
+            date(tr_date),
+            date(en_date),
+        )?;
+    }
+
+    infoln!("found {outdated} outdated translation(s) in '{language}'");
     Ok(())
 }
+
+pub fn stats(repo_path: &Path, list_missing: bool, json: bool, language: &str) -> Result<()> {
+    let languages: Vec<&str> = if language == "en" {
+        let mut languages: Vec<&str> = MORE_INFORMATION
+            .keys()
+            .copied()
+            .filter(|&l| l != "en")
+            .collect();
+        languages.sort_unstable();
+        languages
+    } else {
+        vec![language]
+    };
+
+    let stats = stats::stats(repo_path, &languages, list_missing)?;
+    let mut stdout = io::stdout().lock();
+
+    if json {
+        serde_json::to_writer_pretty(&mut stdout, &stats)
+            .map_err(|e| Error::Msg(format!("failed to serialize statistics: {e}")))?;
+        writeln!(stdout)?;
+        return Ok(());
+    }
+
+    writeln!(
+        stdout,
+        "{}",
+        Paint::new(format!(
+            "{:<10}{:<12}{:>8}{:>12}{:>9}",
+            "LANGUAGE", "PLATFORM", "ENGLISH", "TRANSLATED", "PERCENT"
+        ))
+        .bold()
+    )?;
+    for lang in &stats {
+        for p in &lang.platforms {
+            writeln!(
+                stdout,
+                "{:<10}{:<12}{:>8}{:>12}{:>8.1}%",
+                lang.language, p.platform, p.english, p.translated, p.percentage
+            )?;
+        }
+        writeln!(
+            stdout,
+            "{}",
+            Paint::new(format!(
+                "{:<10}{:<12}{:>8}{:>12}{:>8.1}%",
+                lang.language, "total", lang.english, lang.translated, lang.percentage
+            ))
+            .bold()
+        )?;
+    }
+
+    if list_missing {
+        for lang in &stats {
+            for p in &lang.platforms {
+                let Some(missing) = p.missing.as_ref().filter(|m| !m.is_empty()) else {
+                    continue;
+                };
+                writeln!(
+                    stdout,
+                    "\n{}\n{}",
+                    Paint::new(format!("missing in {}/{}:", lang.language, p.platform)).bold(),
+                    missing.join("\n")
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits `s` into lines at most `width` columns wide.
+fn wrap(s: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    let mut line_width = 0;
+
+    for c in s.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if line_width + char_width > width.max(1) && line_width != 0 {
+            lines.push(String::new());
+            line_width = 0;
+        }
+        lines.last_mut().unwrap().push(c);
+        line_width += char_width;
+    }
+
+    lines
+}
+
+/// Prints `left` and `right` in two columns `width` columns wide, separated by `marker`.
+fn side_by_side(left: &str, right: &str, marker: Paint<&str>, width: usize) -> Result<()> {
+    let left = wrap(left, width);
+    let right = wrap(right, width);
+    let mut stdout = io::stdout().lock();
+
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map_or("", String::as_str);
+        let r = right.get(i).map_or("", String::as_str);
+        let pad = width.saturating_sub(l.width());
+        if i == 0 {
+            writeln!(stdout, "{l}{:pad$} {marker} {r}", "")?;
+        } else {
+            writeln!(stdout, "{l}{:pad$}   {r}", "")?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn compare(
+    repo_path: &Path,
+    page: &[String],
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    if language == "en" {
+        return Err(Error::Msg(
+            "specify the language of the translation using '--language'".to_string(),
+        ));
+    }
+
+    let page = page.join("-").to_lowercase();
+    let (platform, en_path) = find_page(repo_path, platform, "en", &page)?;
+    let english = Page::read(&en_path)?;
+    let translation = Page::read(&existing_page_path(repo_path, &platform, language, &page)?)?;
+
+    let columns = env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .or_else(|| terminal_size().map(|(Width(w), _)| usize::from(w)))
+        .unwrap_or(80);
+    let width = columns.saturating_sub(3) / 2;
+    let same = Paint::new("|").dimmed();
+
+    side_by_side(&english.title, &translation.title, same, width)?;
+    writeln!(io::stdout())?;
+    let en_desc = english.description.iter();
+    let tr_desc = translation.description.iter();
+    for i in 0..en_desc.len().max(tr_desc.len()) {
+        side_by_side(
+            english.description.get(i).map_or("", String::as_str),
+            translation.description.get(i).map_or("", String::as_str),
+            same,
+            width,
+        )?;
+    }
+
+    let (mut changed, mut missing, mut extra) = (0, 0, 0);
+    for row in translation::align(&english, &translation) {
+        let (en, tr, marker) = match row {
+            Alignment::Same(i, j) => (Some(i), Some(j), same),
+            Alignment::Changed(i, j) => {
+                changed += 1;
+                (Some(i), Some(j), Paint::new("~").fg(Color::Yellow).bold())
+            }
+            Alignment::English(i) => {
+                missing += 1;
+                (Some(i), None, Paint::new("<").fg(Color::Red).bold())
+            }
+            Alignment::Translation(j) => {
+                extra += 1;
+                (None, Some(j), Paint::new(">").fg(Color::Red).bold())
+            }
+        };
+        let en = en.map(|i| &english.examples[i]);
+        let tr = tr.map(|j| &translation.examples[j]);
+
+        writeln!(io::stdout())?;
+        side_by_side(
+            &en.map_or(String::new(), |ex| format!("- {}", ex.description)),
+            &tr.map_or(String::new(), |ex| format!("- {}", ex.description)),
+            marker,
+            width,
+        )?;
+        side_by_side(
+            &en.map_or(String::new(), |ex| format!("  {}", ex.command)),
+            &tr.map_or(String::new(), |ex| format!("  {}", ex.command)),
+            marker,
+            width,
+        )?;
+    }
+
+    writeln!(io::stdout())?;
+    infoln!(
+        "{changed} example(s) differ, {missing} missing in the translation, {extra} only in the translation"
+    );
+    Ok(())
+}
+
+pub fn untranslated(
+    repo_path: &Path,
+    pages: &[String],
+    marker: &str,
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    if language == "en" {
+        return Err(Error::Msg(
+            "specify the language of translations to check using '--language'".to_string(),
+        ));
+    }
+
+    let paths: Vec<PathBuf> = if pages.is_empty() {
+        list_pages(repo_path, language)?
+            .into_iter()
+            .map(|(platform, page)| {
+                language_dir(repo_path, language)
+                    .join(platform)
+                    .join(format!("{page}.md"))
+            })
+            .collect()
+    } else {
+        pages
+            .iter()
+            .map(|page| Ok(find_page(repo_path, platform, language, &page.to_lowercase())?.1))
+            .collect::<Result<_>>()?
+    };
+
+    let mut count = 0;
+    for path in paths {
+        let file = path
+            .strip_prefix(language_dir(repo_path, language))
+            .unwrap();
+        let en_path = language_dir(repo_path, "en").join(file);
+        let english = if en_path.is_file() {
+            Some(Page::read(&en_path)?)
+        } else {
+            None
+        };
+
+        let leftovers =
+            translation::untranslated_lines(english.as_ref(), &fs::read_to_string(&path)?, marker)
+                .map_err(|e| e.in_file(&path))?;
+
+        let display_path = path.strip_prefix(repo_path).unwrap_or(&path).display();
+        for (line, leftover) in &leftovers {
+            writeln!(
+                io::stdout(),
+                "{display_path}:{line}: {}",
+                Paint::new(leftover).fg(Color::Yellow)
+            )?;
+        }
+        count += leftovers.len();
+    }
+
+    if count == 0 {
+        infoln!("no untranslated lines found");
+        Ok(())
+    } else {
+        Err(Error::Msg(format!("found {count} untranslated line(s)")))
+    }
+}
+
+pub fn search(
+    repo_path: &Path,
+    pattern: &str,
+    ignore_case: bool,
+    all_languages: bool,
+    scope: SearchScope,
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| Error::Msg(format!("invalid regular expression: {e}")))?;
+
+    let languages = if all_languages {
+        list_languages(repo_path)?
+    } else {
+        vec![language.to_string()]
+    };
+
+    let mut stdout = io::stdout().lock();
+    let mut matches = 0;
+
+    for language in &languages {
+        for (page_platform, page) in list_pages(repo_path, language)? {
+            if platform.is_some_and(|p| p.to_string() != page_platform) {
+                continue;
+            }
+
+            let path = language_dir(repo_path, language)
+                .join(&page_platform)
+                .join(format!("{page}.md"));
+            let contents = fs::read_to_string(&path)?;
+            let mut header_printed = false;
+
+            for (i, line) in contents.lines().enumerate() {
+                if !scope.includes(line) || !regex.is_match(line) {
+                    continue;
+                }
+
+                if !header_printed {
+                    writeln!(
+                        stdout,
+                        "{}",
+                        Paint::new(format!("{language}/{page_platform}/{page}"))
+                            .fg(Color::Magenta)
+                            .bold()
+                    )?;
+                    header_printed = true;
+                }
+
+                let mut highlighted = String::new();
+                let mut last = 0;
+                for m in regex.find_iter(line) {
+                    highlighted.push_str(&line[last..m.start()]);
+                    highlighted.push_str(&Paint::new(m.as_str()).fg(Color::Red).bold().to_string());
+                    last = m.end();
+                }
+                highlighted.push_str(&line[last..]);
+
+                writeln!(
+                    stdout,
+                    "{}: {highlighted}",
+                    Paint::new(i + 1).fg(Color::Green)
+                )?;
+                matches += 1;
+            }
+        }
+    }
+
+    if matches == 0 {
+        Err(Error::Msg("no matches found".to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct ListedPage {
+    name: String,
+    platform: String,
+    path: PathBuf,
+    size: u64,
+    last_commit: Option<String>,
+}
+
+pub fn list(
+    repo_path: &Path,
+    filters: &ListFilters,
+    format: ListFormat,
+    language: &str,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    let all_pages = list_pages(repo_path, language)?;
+    let modified = if filters.modified {
+        changed_pages(repo_path)?
+    } else {
+        vec![]
+    };
+    let translated: HashSet<(String, String)> = match &filters.untranslated {
+        Some(other) => {
+            check_language(other).map_err(Error::Msg)?;
+            list_pages(repo_path, other)?.into_iter().collect()
+        }
+        None => HashSet::new(),
+    };
+    let mut platform_counts: HashMap<&str, usize> = HashMap::new();
+    if filters.multi_platform {
+        for (_, name) in &all_pages {
+            *platform_counts.entry(name).or_default() += 1;
+        }
+    }
+
+    let mut pages = vec![];
+    for (page_platform, name) in &all_pages {
+        if platform.is_some_and(|p| p.to_string() != *page_platform) {
+            continue;
+        }
+
+        let file = PathBuf::from(language_dir(repo_path, language).file_name().unwrap())
+            .join(page_platform)
+            .join(format!("{name}.md"));
+
+        if filters.multi_platform && platform_counts[name.as_str()] < 2 {
+            continue;
+        }
+        if translated.contains(&(page_platform.clone(), name.clone())) {
+            continue;
+        }
+        if filters.modified && !modified.contains(&repo_path.join(&file)) {
+            continue;
+        }
+        if filters.aliases && !Page::read(&file).is_ok_and(|p| p.alias_of().is_some()) {
+            continue;
+        }
+
+        pages.push(ListedPage {
+            name: name.clone(),
+            platform: page_platform.clone(),
+            size: fs::metadata(&file)?.len(),
+            path: file,
+            last_commit: None,
+        });
+    }
+
+    if matches!(format, ListFormat::Long | ListFormat::Json) {
+        let paths: Vec<PathBuf> = pages.iter().map(|p| p.path.clone()).collect();
+        let dates = if paths.is_empty() {
+            HashMap::new()
+        } else {
+            last_commit_dates(&paths)?
+        };
+        for page in &mut pages {
+            page.last_commit = dates.get(&page.path).map(|(_, date)| date.clone());
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    match format {
+        ListFormat::Plain => {
+            for page in &pages {
+                if platform.is_some() {
+                    writeln!(stdout, "{}", page.name)?;
+                } else {
+                    writeln!(stdout, "{}/{}", page.platform, page.name)?;
+                }
+            }
+        }
+        ListFormat::Long => {
+            for page in &pages {
+                writeln!(
+                    stdout,
+                    "{:<50} {:>6} {}",
+                    page.path.display(),
+                    page.size,
+                    page.last_commit.as_deref().unwrap_or("uncommitted")
+                )?;
+            }
+        }
+        ListFormat::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &pages)
+                .map_err(|e| Error::Msg(format!("failed to serialize the page list: {e}")))?;
+            writeln!(stdout)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns an error if any of `copies` is not tracked by git, so that `git mv` does not fail
+/// after some of them have already been moved.
+fn check_tracked(repo_path: &Path, copies: &[PageCopy]) -> Result<()> {
+    let untracked = untracked_copies(repo_path, copies)?;
+    if untracked.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::Msg(format!(
+        "these pages are not tracked by git, add or remove them first: {}",
+        untracked
+            .iter()
+            .map(|copy| {
+                copy.path
+                    .strip_prefix(repo_path)
+                    .unwrap_or(&copy.path)
+                    .display()
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    )))
+}
+
+/// Replaces `tldr <old>` (matched by `tldr_old`) with `tldr <new>` in command lines and,
+/// in alias pages of `old`, `` `old` `` with `` `new` `` in the description.
+/// Returns `None` if nothing has changed.
+fn replace_references(contents: &str, tldr_old: &Regex, old: &str, new: &str) -> Option<String> {
+    let is_alias = contents.contains(&format!("`{old}`"))
+        && Page::parse(contents).is_ok_and(|p| p.alias_of() == Some(old));
+
+    let mut changed = false;
+    let lines: Vec<String> = contents
+        .split('\n')
+        .map(|line| {
+            let replaced = if line.starts_with('`') {
+                tldr_old
+                    .replace_all(line, format!("${{1}}{new}${{2}}"))
+                    .into_owned()
+            } else if line.starts_with('>') && is_alias {
+                line.replace(&format!("`{old}`"), &format!("`{new}`"))
+            } else {
+                return line.to_string();
+            };
+            changed |= replaced != line;
+            replaced
+        })
+        .collect();
+
+    changed.then(|| lines.join("\n"))
+}
+
+pub fn rename(repo_path: &Path, old: &str, new: &str) -> Result<()> {
+    let old = old.to_lowercase().replace(' ', "-");
+    let new = new.to_lowercase().replace(' ', "-");
+
+    let copies = page_copies(repo_path, None, &old)?;
+    if copies.is_empty() {
+        return Err(page_not_found(repo_path, None, "en", &old));
+    }
+
+    for copy in &copies {
+        if copy.path.with_file_name(format!("{new}.md")).exists() {
+            return Err(Error::Msg(format!(
+                "page '{}/{}/{new}' already exists",
+                copy.language, copy.platform
+            )));
+        }
+    }
+
+    check_tracked(repo_path, &copies)?;
+
+    let mut renamed = vec![];
+    for copy in &copies {
+        let new_path = copy.path.with_file_name(format!("{new}.md"));
+        spawn(&mut cmd!("git", "mv", &copy.path, &new_path))?;
+
+        // Keep the style of the title, e.g. 'git commit' for 'git-commit.md'.
+        let contents = fs::read_to_string(&new_path)?;
+        if let Some((title_line, rest)) = contents.split_once('\n') {
+            let title = title_line.trim_start_matches('#').trim();
+            if title.replace(' ', "-").to_lowercase() == old {
+                let new_title = if title.contains(' ') {
+                    new.replace('-', " ")
+                } else {
+                    new.clone()
+                };
+                fs::write(&new_path, format!("# {new_title}\n{rest}"))?;
+            }
+        }
+        renamed.push(new_path);
+    }
+
+    let tldr_old = Regex::new(&format!(r"(tldr\s+){}([^\w-]|$)", regex::escape(&old))).unwrap();
+    let mut updated = vec![];
+    for path in all_pages(repo_path)? {
+        let contents = fs::read_to_string(&path)?;
+        if let Some(replaced) = replace_references(&contents, &tldr_old, &old, &new) {
+            fs::write(&path, replaced)?;
+            updated.push(path);
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", Paint::new("renamed:").bold())?;
+    for path in &renamed {
+        writeln!(
+            stdout,
+            "  {}",
+            path.strip_prefix(repo_path).unwrap_or(path).display()
+        )?;
+    }
+    if !updated.is_empty() {
+        writeln!(stdout, "{}", Paint::new("updated references:").bold())?;
+        for path in &updated {
+            writeln!(
+                stdout,
+                "  {}",
+                path.strip_prefix(repo_path).unwrap_or(path).display()
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn move_page(repo_path: &Path, page: &str, from: &Platform, to: &Platform) -> Result<()> {
+    let page = page.to_lowercase().replace(' ', "-");
+    from.ensure_exists(repo_path)?;
+    to.ensure_exists(repo_path)?;
+
+    let copies = page_copies(repo_path, Some(from), &page)?;
+    if copies.is_empty() {
+        return Err(page_not_found(repo_path, Some(from), "en", &page));
+    }
+
+    let existing: Vec<String> = page_copies(repo_path, Some(to), &page)?
+        .into_iter()
+        .map(|copy| copy.language)
+        .collect();
+    if !existing.is_empty() {
+        return Err(Error::Msg(format!(
+            "page '{to}/{page}' already exists in: {}",
+            existing.join(", ")
+        )));
+    }
+
+    check_tracked(repo_path, &copies)?;
+
+    let mut moved = vec![];
+    for copy in copies {
+        let new_path = page_path(repo_path, to, &copy.language, &page);
+        fs::create_dir_all(new_path.parent().unwrap())?;
+        spawn(&mut cmd!("git", "mv", &copy.path, &new_path))?;
+        moved.push(copy.language);
+    }
+
+    infoln!(
+        "moved '{page}' from {from} to {to} in: {}",
+        moved.join(", ")
+    );
+    Ok(())
+}
+
+pub fn remove(
+    repo_path: &Path,
+    page: &[String],
+    language: Option<&str>,
+    platform: Option<&Platform>,
+) -> Result<()> {
+    let page = page.join("-").to_lowercase();
+    let (platform, _) = find_page(repo_path, platform, language.unwrap_or("en"), &page)?;
+
+    let copies: Vec<PageCopy> = page_copies(repo_path, Some(&platform), &page)?
+        .into_iter()
+        .filter(|copy| language.map_or(true, |l| l == copy.language))
+        .collect();
+
+    writeln!(io::stdout(), "The following pages will be removed:")?;
+    for copy in &copies {
+        writeln!(
+            io::stdout(),
+            "  {}",
+            copy.path
+                .strip_prefix(repo_path)
+                .unwrap_or(&copy.path)
+                .display()
+        )?;
+    }
+
+    // Alias pages in the affected languages that point to the removed page.
+    let languages: Vec<&str> = copies.iter().map(|c| c.language.as_str()).collect();
+    for path in all_pages(repo_path)? {
+        if !languages.contains(&page_language(&path).as_str()) {
+            continue;
+        }
+        if Page::read(&path).is_ok_and(|p| p.alias_of() == Some(page.as_str())) {
+            warnln!(
+                "'{}' is an alias of this page",
+                path.strip_prefix(repo_path).unwrap_or(&path).display()
+            );
+        }
+    }
+
+    for copy in modified_copies(repo_path, &copies)? {
+        warnln!(
+            "'{}' has uncommitted changes, which will be lost",
+            copy.path
+                .strip_prefix(repo_path)
+                .unwrap_or(&copy.path)
+                .display()
+        );
+    }
+
+    if !confirm("Remove these pages?")? {
+        infoln!("no pages removed.");
+        return Ok(());
+    }
+
+    // 'git rm' fails for files which are not tracked, so they are deleted directly.
+    let untracked = untracked_copies(repo_path, &copies)?;
+    for copy in &untracked {
+        fs::remove_file(&copy.path)?;
+    }
+    let tracked: Vec<&PathBuf> = copies
+        .iter()
+        .filter(|copy| !untracked.iter().any(|u| u.path == copy.path))
+        .map(|copy| &copy.path)
+        .collect();
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
+    let mut cmd = cmd!("git", "rm", "--force", "--");
+    cmd.args(tracked);
+    spawn(&mut cmd)
+}
@@ -0,0 +1,393 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+
+use serde::{Deserialize, Serialize};
+
+use crate::args::Platform;
+use crate::consts::MORE_INFORMATION;
+use crate::error::{Error, Result};
+use crate::repo::Repository;
+use crate::util::infoln;
+
+/// The URL scheme used to clone the fork.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    #[default]
+    Ssh,
+    Https,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RepositoryConfig {
+    /// The path to the fork on the git host (`username/repo_name`).
+    pub fork: String,
+    #[serde(default)]
+    pub scheme: Scheme,
+    /// The git host of the fork, if it is not GitHub.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// The URL of the `upstream` remote, if it is not the official tldr repository.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    /// The location of the local clone, if it is not in the cache directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Defaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Tools {
+    /// Overrides `$EDITOR`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// The command used to render pages in `tlt view`. The page path is appended to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renderer: Option<String>,
+}
+
+/// Documentation host rules, added to the built-in ones.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DocHostsConfig {
+    /// Hosts which produce a warning when used in a documentation URL.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub avoid: Vec<String>,
+    /// Preferred hosts for each platform.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub preferred: BTreeMap<String, Vec<String>>,
+}
+
+/// The name of the settings in the `repository` and `defaults` sections, used when no profile is active.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A named set of settings overriding the `repository` and `defaults` sections.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub fork: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The active profile. If not set, only the `repository` and `defaults` sections are used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub repository: RepositoryConfig,
+    #[serde(default)]
+    pub defaults: Defaults,
+    #[serde(default)]
+    pub tools: Tools,
+    #[serde(default)]
+    pub doc_hosts: DocHostsConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Checks that `fork` is a repository path on the git host (`owner/name`, or e.g. `group/subgroup/name`
+/// on GitLab) and returns it without surrounding whitespace and a `.git` suffix.
+pub fn check_fork(fork: &str) -> StdResult<String, String> {
+    let fork = fork.trim();
+    let fork = fork.strip_suffix(".git").unwrap_or(fork);
+
+    let valid = fork.contains('/')
+        && !fork.contains(char::is_whitespace)
+        && fork
+            .split('/')
+            .all(|segment| !matches!(segment, "" | "." | ".."));
+
+    if valid {
+        Ok(fork.to_string())
+    } else {
+        Err(format!(
+            "invalid fork '{fork}': expected a repository path like 'owner/name', e.g. 'octocat/tldr'"
+        ))
+    }
+}
+
+/// Checks that `host` is a host name, e.g. `github.com`.
+fn check_host(host: &str) -> StdResult<(), String> {
+    if host.is_empty() || host.contains(['/', '@']) || host.contains(char::is_whitespace) {
+        Err(format!(
+            "expected a host name like 'github.com', got '{host}'"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn check_language(language: &str) -> StdResult<(), String> {
+    if MORE_INFORMATION.contains_key(language) {
+        Ok(())
+    } else {
+        Err(format!("there are no translations in '{language}'"))
+    }
+}
+
+/// Checks that `url` is a URL git can clone from. Local repositories must exist.
+fn check_upstream(url: &str) -> StdResult<(), String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        if !Path::new(path).exists() {
+            return Err(format!("'{path}' does not exist"));
+        }
+        return Ok(());
+    }
+
+    let has_scheme = ["https://", "http://", "ssh://", "git://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme));
+    // The scp-like syntax: `user@host:path`.
+    let is_scp = url
+        .split_once(':')
+        .is_some_and(|(host, path)| host.contains('@') && !path.is_empty());
+
+    if has_scheme || is_scp {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{url}' is not a git URL (expected https://, ssh://, git://, file:// or user@host:path)"
+        ))
+    }
+}
+
+impl Config {
+    pub fn locate() -> PathBuf {
+        dirs::config_dir()
+            .unwrap()
+            .join(env!("CARGO_PKG_NAME"))
+            .join("config.toml")
+    }
+
+    /// The one-line config file used before `config.toml`.
+    fn locate_legacy() -> PathBuf {
+        Self::locate().with_file_name("fork")
+    }
+
+    pub fn write(&self) -> Result<()> {
+        let location = Self::locate();
+        fs::create_dir_all(location.parent().unwrap())?;
+
+        // Serializing these structs cannot fail.
+        fs::write(&location, toml::to_string(self).unwrap())
+            .map_err(|e| Error::Msg(format!("failed to write to the config file: {e}")))
+    }
+
+    fn read() -> Result<Self> {
+        let location = Self::locate();
+        let config: Self = toml::from_str(&fs::read_to_string(&location)?)
+            .map_err(|e| Error::Msg(format!("'{}': {e}", location.display())))?;
+
+        let invalid = |key: &str, msg: &str| {
+            Error::Msg(format!(
+                "'{}': invalid value for '{key}': {msg}",
+                location.display()
+            ))
+        };
+
+        check_fork(&config.repository.fork).map_err(|e| invalid("repository.fork", &e))?;
+        if let Some(host) = &config.repository.host {
+            check_host(host).map_err(|e| invalid("repository.host", &e))?;
+        }
+        if let Some(upstream) = &config.repository.upstream {
+            check_upstream(upstream).map_err(|e| invalid("repository.upstream", &e))?;
+        }
+        if let Some(language) = &config.defaults.language {
+            check_language(language).map_err(|e| invalid("defaults.language", &e))?;
+        }
+        if let Some(platform) = &config.defaults.platform {
+            platform
+                .parse::<Platform>()
+                .map_err(|e| invalid("defaults.platform", &e))?;
+        }
+
+        for host in &config.doc_hosts.avoid {
+            check_host(host).map_err(|e| invalid("doc_hosts.avoid", &e))?;
+        }
+        for (platform, hosts) in &config.doc_hosts.preferred {
+            let key = format!("doc_hosts.preferred.{platform}");
+            platform
+                .parse::<Platform>()
+                .map_err(|e| invalid(&key, &e))?;
+            for host in hosts {
+                check_host(host).map_err(|e| invalid(&key, &e))?;
+            }
+        }
+
+        for (name, profile) in &config.profiles {
+            check_fork(&profile.fork).map_err(|e| invalid(&format!("profiles.{name}.fork"), &e))?;
+            if let Some(language) = &profile.language {
+                check_language(language)
+                    .map_err(|e| invalid(&format!("profiles.{name}.language"), &e))?;
+            }
+        }
+        if let Some(name) = &config.profile {
+            config.profile(name).map_err(|e| invalid("profile", &e))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the profile called `name`, or an error listing the existing profiles.
+    pub fn profile(&self, name: &str) -> StdResult<&Profile, String> {
+        self.profiles.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            format!(
+                "profile '{name}' does not exist (available: {})",
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )
+        })
+    }
+
+    /// Overrides the `repository` and `defaults` sections with the profile called `name`,
+    /// or the active profile from the config file if `name` is `None`.
+    /// A profile without a path uses its own repository in the cache directory.
+    ///
+    /// Returns `false` if no profile was applied (i.e. the profile is `default`).
+    pub fn apply_profile(&mut self, name: Option<&str>) -> Result<bool> {
+        let name = name.or(self.profile.as_deref()).unwrap_or(DEFAULT_PROFILE);
+        if name == DEFAULT_PROFILE {
+            return Ok(false);
+        }
+
+        let profile = self.profile(name).map_err(Error::Msg)?;
+        let fork = check_fork(&profile.fork).map_err(Error::Msg)?;
+        let path = profile
+            .path
+            .clone()
+            .unwrap_or_else(|| Repository::locate_profile(name));
+        let language = profile.language.clone();
+
+        self.repository.fork = fork;
+        self.repository.path = Some(path);
+        if language.is_some() {
+            self.defaults.language = language;
+        }
+
+        Ok(true)
+    }
+
+    /// Converts the old `fork` file to `config.toml` and removes it.
+    fn migrate() -> Result<Self> {
+        let legacy = Self::locate_legacy();
+        infoln!(
+            "migrating '{}' to '{}'...",
+            legacy.display(),
+            Self::locate().display()
+        );
+
+        let contents = fs::read_to_string(&legacy)?;
+        let config = Self {
+            repository: RepositoryConfig {
+                fork: contents
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        config.write()?;
+        fs::remove_file(legacy)?;
+
+        Ok(config)
+    }
+
+    /// Prompts for the fork if it is not given.
+    fn prompt_fork() -> Result<String> {
+        writeln!(
+            io::stdout(),
+            "Please enter the github path to your fork (username/repo_name): "
+        )?;
+        io::stdout().flush()?;
+
+        let mut fork = String::new();
+        if io::stdin().read_line(&mut fork)? == 0 {
+            return Err(Error::Msg(
+                "no fork given. Use 'tlt setup --fork <OWNER/NAME>' to set it non-interactively."
+                    .to_string(),
+            ));
+        }
+
+        Ok(fork)
+    }
+
+    /// Saves the fork and the URL scheme, keeping the rest of the config.
+    pub fn setup(fork: Option<&str>, https: bool) -> Result<Self> {
+        let mut config = if Self::locate().is_file() {
+            Self::read()?
+        } else {
+            Self::default()
+        };
+
+        let fork = match fork {
+            Some(fork) => fork.to_string(),
+            None => Self::prompt_fork()?,
+        };
+        config.repository.fork = check_fork(&fork).map_err(Error::Msg)?;
+        if https {
+            config.repository.scheme = Scheme::Https;
+        }
+        config.write()?;
+
+        Ok(config)
+    }
+
+    /// Reads the config file, migrating the old one if needed. Returns `None` if there is none.
+    pub fn get() -> Result<Option<Self>> {
+        if Self::locate().is_file() {
+            Self::read().map(Some)
+        } else if Self::locate_legacy().is_file() {
+            Self::migrate().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forks() {
+        assert_eq!(check_fork(" octocat/tldr.git\n").unwrap(), "octocat/tldr");
+        assert_eq!(check_fork("my_org/tldr").unwrap(), "my_org/tldr");
+        assert_eq!(check_fork("group/sub/tldr").unwrap(), "group/sub/tldr");
+
+        for fork in [
+            "",
+            "tldr",
+            "/tldr",
+            "octocat/",
+            "a//b",
+            "../tldr",
+            "my org/tldr",
+        ] {
+            assert!(check_fork(fork).is_err(), "{fork}");
+        }
+    }
+}
@@ -2,6 +2,9 @@ use std::collections::HashMap;
 
 use once_cell::sync::Lazy;
 
+/// The default marker for descriptions that have not been translated yet.
+pub const UNTRANSLATED_MARKER: &str = "TODO:";
+
 pub static MORE_INFORMATION: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
     let mut m = HashMap::new();
     m.insert("en", "More information");
@@ -279,3 +282,48 @@ pub static ALIAS_PAGES: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
 
     m
 });
+
+/// Query parameters used for tracking, which are removed from documentation URLs.
+/// A trailing `*` matches any suffix.
+pub const TRACKING_PARAMS: &[&str] = &["utm_*", "fbclid", "gclid", "msclkid", "mc_cid", "mc_eid"];
+
+/// Hosts which are known to serve documentation over HTTPS, in addition to the preferred hosts.
+pub const HTTPS_HOSTS: &[&str] = &[
+    "github.com",
+    "gitlab.com",
+    "codeberg.org",
+    "sourceforge.net",
+    "docs.rs",
+    "crates.io",
+    "pypi.org",
+    "www.npmjs.com",
+    "www.gnu.org",
+    "wiki.archlinux.org",
+];
+
+/// Unofficial mirrors of man pages, which are often outdated.
+pub const AVOIDED_DOC_HOSTS: &[&str] = &[
+    "linux.die.net",
+    "man.he.net",
+    "manpages.org",
+    "linuxcommand.org",
+    "explainshell.com",
+];
+
+/// Preferred hosts for the documentation of commands, per platform.
+pub static PREFERRED_DOC_HOSTS: Lazy<HashMap<&str, &[&str]>> = Lazy::new(|| {
+    let mut m: HashMap<&str, &[&str]> = HashMap::new();
+    m.insert("android", &["developer.android.com"]);
+    m.insert("freebsd", &["man.freebsd.org"]);
+    m.insert("linux", &["man7.org", "manned.org"]);
+    m.insert("netbsd", &["man.netbsd.org"]);
+    m.insert("openbsd", &["man.openbsd.org"]);
+    m.insert(
+        "osx",
+        &["keith.github.io", "ss64.com", "developer.apple.com"],
+    );
+    m.insert("sunos", &["docs.oracle.com"]);
+    m.insert("windows", &["learn.microsoft.com"]);
+
+    m
+});
@@ -1,5 +1,6 @@
 use std::io;
 use std::io::Write;
+use std::path::Path;
 use std::process::exit;
 use std::result::Result as StdResult;
 
@@ -23,6 +24,15 @@ fn error(msg: &str) {
 }
 
 impl Error {
+    /// Prefix the error message with `path`, turning it into a `Msg`.
+    pub fn in_file(self, path: &Path) -> Self {
+        match self {
+            Error::Msg(e) | Error::ChildProcess(e) => {
+                Error::Msg(format!("{}: {e}", path.display()))
+            }
+        }
+    }
+
     /// Print the error message to stderr and exit.
     pub fn exit(self) -> ! {
         exit(match self {
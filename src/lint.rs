@@ -0,0 +1,444 @@
+use std::fmt::{self, Display};
+
+use crate::consts::MORE_INFORMATION;
+
+/// A rule from the tldr-lint rule set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rule {
+    LeadingWhitespace = 1,
+    SingleSpace,
+    DescriptionCapital,
+    DescriptionPeriod,
+    ExampleColon,
+    InformationLabel,
+    InformationBrackets,
+    SectionSeparator,
+    FinalNewline,
+    LineEndings,
+    MultipleEmptyLines,
+    Tabs,
+    TitleCharacters,
+    TrailingWhitespace,
+    ExampleCapital,
+    CommandBackticks,
+    UnbalancedPlaceholders,
+    MultipleInformationLinks,
+    TitleFileName,
+    UnexpectedLine,
+    CommandWhitespace,
+}
+
+impl Rule {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::LeadingWhitespace => "File should contain no leading whitespace",
+            Self::SingleSpace => "A single space should precede a sentence",
+            Self::DescriptionCapital => "Descriptions should start with a capital letter",
+            Self::DescriptionPeriod => "Command descriptions should end in a period",
+            Self::ExampleColon => {
+                "Example descriptions should end in a colon with no trailing characters"
+            }
+            Self::InformationLabel => {
+                "Label for information link should be spelled exactly `More information: ` (or its translation)"
+            }
+            Self::InformationBrackets => "Information link should be surrounded with angle brackets",
+            Self::SectionSeparator => "A single blank line should separate sections",
+            Self::FinalNewline => "Page should contain a newline at end of file",
+            Self::LineEndings => "Only Unix-style line endings should be used",
+            Self::MultipleEmptyLines => "Page never contains more than a single empty line",
+            Self::Tabs => "Page should contain no tabs",
+            Self::TitleCharacters => "Title should be alphanumeric with dashes, underscores or spaces",
+            Self::TrailingWhitespace => "Page should contain no trailing whitespace",
+            Self::ExampleCapital => "Example descriptions should start with a capital letter",
+            Self::CommandBackticks => "Command examples should be surrounded with single backticks",
+            Self::UnbalancedPlaceholders => "Placeholders should be opened with `{{` and closed with `}}`",
+            Self::MultipleInformationLinks => "Page should only include a single information link",
+            Self::TitleFileName => "Page should start with a title matching its file name",
+            Self::UnexpectedLine => "Lines should start with `#`, `>`, `-` or a backtick",
+            Self::CommandWhitespace => "Command example should not begin or end in whitespace",
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TLDR{:03}", *self as u8)
+    }
+}
+
+/// A rule violation found on a line (1-based).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub rule: Rule,
+}
+
+/// The kind of the last non-empty line seen.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Start,
+    Title,
+    Description,
+    Example,
+    Command,
+}
+
+fn starts_lowercase(s: &str) -> bool {
+    s.chars().next().is_some_and(char::is_lowercase)
+}
+
+fn check_description(
+    text: &str,
+    label: &str,
+    information_links: &mut usize,
+    rules: &mut Vec<Rule>,
+) {
+    // A line is an information link if it has the expected label or an URL in angle brackets.
+    if let Some((link_label, url)) = text.split_once(": ") {
+        if link_label == label || (url.starts_with('<') && url.contains("://")) {
+            *information_links += 1;
+            if *information_links > 1 {
+                rules.push(Rule::MultipleInformationLinks);
+            }
+            if link_label != label {
+                rules.push(Rule::InformationLabel);
+            }
+            if !url.starts_with('<') || !url.ends_with(">.") {
+                rules.push(Rule::InformationBrackets);
+            }
+            return;
+        }
+    }
+
+    if starts_lowercase(text) {
+        rules.push(Rule::DescriptionCapital);
+    }
+    if !text.ends_with('.') {
+        rules.push(Rule::DescriptionPeriod);
+    }
+}
+
+fn check_example(text: &str, rules: &mut Vec<Rule>) {
+    if starts_lowercase(text) {
+        rules.push(Rule::ExampleCapital);
+    }
+    if !text.ends_with(':') || text.ends_with("::") {
+        rules.push(Rule::ExampleColon);
+    }
+}
+
+fn check_command(line: &str, rules: &mut Vec<Rule>) {
+    let Some(command) = line
+        .strip_prefix('`')
+        .and_then(|s| s.strip_suffix('`'))
+        .filter(|s| !s.starts_with('`') && !s.ends_with('`') && !s.is_empty())
+    else {
+        rules.push(Rule::CommandBackticks);
+        return;
+    };
+
+    if command.trim() != command {
+        rules.push(Rule::CommandWhitespace);
+    }
+    if command.matches("{{").count() != command.matches("}}").count() {
+        rules.push(Rule::UnbalancedPlaceholders);
+    }
+}
+
+/// Checks the contents of a page named `name` (the file name without `.md`) in `language`.
+pub fn lint(s: &str, name: &str, language: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut add = |line, rule| diagnostics.push(Diagnostic { line, rule });
+
+    let lines: Vec<&str> = s.strip_suffix('\n').unwrap_or(s).split('\n').collect();
+    let label = MORE_INFORMATION
+        .get(language)
+        .copied()
+        .unwrap_or("More information");
+
+    if s.starts_with(char::is_whitespace) {
+        add(1, Rule::LeadingWhitespace);
+    }
+    if !s.is_empty() && !s.ends_with('\n') {
+        add(lines.len(), Rule::FinalNewline);
+    }
+
+    let mut section = Section::Start;
+    let mut empty_lines = 0;
+    let mut information_links = 0;
+
+    for (i, raw_line) in lines.iter().enumerate() {
+        let lnum = i + 1;
+        let line = match raw_line.strip_suffix('\r') {
+            Some(line) => {
+                add(lnum, Rule::LineEndings);
+                line
+            }
+            None => raw_line,
+        };
+
+        if line.contains('\t') {
+            add(lnum, Rule::Tabs);
+        }
+        if line.trim_end() != line {
+            add(lnum, Rule::TrailingWhitespace);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            empty_lines += 1;
+            if empty_lines == 2 {
+                add(lnum, Rule::MultipleEmptyLines);
+            }
+            continue;
+        }
+
+        // Every line except consecutive description lines starts a new section.
+        let (prefix, rest) = line.split_at(line.chars().next().unwrap().len_utf8());
+        let new_section = match prefix {
+            "#" => Section::Title,
+            ">" => Section::Description,
+            "-" => Section::Example,
+            "`" => Section::Command,
+            _ => {
+                add(lnum, Rule::UnexpectedLine);
+                section = Section::Start;
+                empty_lines = 0;
+                continue;
+            }
+        };
+        let continues_description =
+            section == Section::Description && new_section == Section::Description;
+        if section != Section::Start && empty_lines == 0 && !continues_description {
+            add(lnum, Rule::SectionSeparator);
+        }
+        section = new_section;
+        empty_lines = 0;
+
+        if new_section != Section::Command
+            && (!rest.starts_with(' ') || rest[1..].starts_with(char::is_whitespace))
+        {
+            add(lnum, Rule::SingleSpace);
+        }
+        let text = rest.trim_start();
+
+        let mut rules = vec![];
+        match new_section {
+            Section::Title => {
+                if lnum != 1 || text.replace(' ', "-").to_lowercase() != name {
+                    rules.push(Rule::TitleFileName);
+                }
+                if !text
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || " -_.+!".contains(c))
+                {
+                    rules.push(Rule::TitleCharacters);
+                }
+            }
+            Section::Description => {
+                check_description(text, label, &mut information_links, &mut rules);
+            }
+            Section::Example => check_example(text, &mut rules),
+            Section::Command => check_command(line, &mut rules),
+            Section::Start => unreachable!(),
+        }
+
+        for rule in rules {
+            add(lnum, rule);
+        }
+    }
+
+    if !lines.iter().any(|line| line.starts_with('#')) {
+        add(1, Rule::TitleFileName);
+    }
+
+    diagnostics.sort();
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "# tar
+
+> Archiving utility.
+> More information: <https://www.gnu.org/software/tar>.
+
+- Extract a (compressed) archive file into the current directory:
+
+`tar xf {{path/to/source.tar[.gz|.bz2|.xz]}}`
+";
+
+    /// Lints `PAGE` with `from` replaced by `to` and returns the violated rules.
+    fn rules(from: &str, to: &str) -> Vec<Rule> {
+        lint(&PAGE.replacen(from, to, 1), "tar", "en")
+            .into_iter()
+            .map(|d| d.rule)
+            .collect()
+    }
+
+    #[test]
+    fn valid_page() {
+        assert_eq!(rules("", ""), []);
+        assert_eq!(
+            lint(
+                &PAGE.replace("More information", "Más información"),
+                "tar",
+                "es"
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn leading_whitespace() {
+        assert!(rules("# tar", " # tar").contains(&Rule::LeadingWhitespace));
+    }
+
+    #[test]
+    fn single_space() {
+        assert_eq!(rules("> Archiving", ">  Archiving"), [Rule::SingleSpace]);
+        assert_eq!(rules("- Extract", "-Extract"), [Rule::SingleSpace]);
+    }
+
+    #[test]
+    fn description_capital() {
+        assert_eq!(
+            rules("> Archiving", "> archiving"),
+            [Rule::DescriptionCapital]
+        );
+    }
+
+    #[test]
+    fn description_period() {
+        assert_eq!(rules("utility.", "utility"), [Rule::DescriptionPeriod]);
+    }
+
+    #[test]
+    fn example_colon() {
+        assert_eq!(rules("directory:", "directory"), [Rule::ExampleColon]);
+        assert_eq!(rules("directory:", "directory::"), [Rule::ExampleColon]);
+    }
+
+    #[test]
+    fn information_label() {
+        assert_eq!(
+            rules("More information", "More info"),
+            [Rule::InformationLabel]
+        );
+    }
+
+    #[test]
+    fn information_brackets() {
+        assert_eq!(
+            rules(
+                "<https://www.gnu.org/software/tar>.",
+                "https://www.gnu.org/software/tar."
+            ),
+            [Rule::InformationBrackets]
+        );
+    }
+
+    #[test]
+    fn description_with_url() {
+        // Only lines with the expected label or a bracketed URL are information links.
+        assert_eq!(
+            rules(
+                "> Archiving utility.",
+                "> See also: https://example.com/tar."
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn section_separator() {
+        assert_eq!(rules("# tar\n\n", "# tar\n"), [Rule::SectionSeparator]);
+    }
+
+    #[test]
+    fn final_newline() {
+        assert_eq!(
+            lint(PAGE.trim_end(), "tar", "en"),
+            [Diagnostic {
+                line: 8,
+                rule: Rule::FinalNewline
+            }]
+        );
+    }
+
+    #[test]
+    fn line_endings() {
+        assert_eq!(rules("# tar\n", "# tar\r\n"), [Rule::LineEndings]);
+    }
+
+    #[test]
+    fn multiple_empty_lines() {
+        assert_eq!(rules("# tar\n", "# tar\n\n"), [Rule::MultipleEmptyLines]);
+    }
+
+    #[test]
+    fn tabs() {
+        assert_eq!(rules("tar xf", "tar\txf"), [Rule::Tabs]);
+    }
+
+    #[test]
+    fn title_characters() {
+        assert_eq!(
+            lint(&PAGE.replace("# tar", "# tar?"), "tar?", "en")
+                .into_iter()
+                .map(|d| d.rule)
+                .collect::<Vec<_>>(),
+            [Rule::TitleCharacters]
+        );
+    }
+
+    #[test]
+    fn trailing_whitespace() {
+        assert_eq!(rules("utility.", "utility. "), [Rule::TrailingWhitespace]);
+    }
+
+    #[test]
+    fn example_capital() {
+        assert_eq!(rules("- Extract", "- extract"), [Rule::ExampleCapital]);
+    }
+
+    #[test]
+    fn command_backticks() {
+        assert_eq!(rules(".xz]}}`", ".xz]}}"), [Rule::CommandBackticks]);
+        assert_eq!(rules("`tar", "``tar"), [Rule::CommandBackticks]);
+    }
+
+    #[test]
+    fn unbalanced_placeholders() {
+        assert_eq!(rules("{{path", "{path"), [Rule::UnbalancedPlaceholders]);
+    }
+
+    #[test]
+    fn multiple_information_links() {
+        assert_eq!(
+            rules(
+                "> Archiving utility.",
+                "> More information: <https://example.com>."
+            ),
+            [Rule::MultipleInformationLinks]
+        );
+    }
+
+    #[test]
+    fn title_file_name() {
+        assert_eq!(rules("# tar", "# bsdtar"), [Rule::TitleFileName]);
+        assert_eq!(rules("# tar\n\n", ""), [Rule::TitleFileName]);
+    }
+
+    #[test]
+    fn unexpected_line() {
+        assert_eq!(rules("utility.", "utility.\nstray"), [Rule::UnexpectedLine]);
+    }
+
+    #[test]
+    fn command_whitespace() {
+        assert_eq!(rules("`tar", "` tar"), [Rule::CommandWhitespace]);
+    }
+}
@@ -3,25 +3,34 @@
 
 mod args;
 mod commands;
+mod config;
 mod consts;
+mod doc_url;
 mod error;
+mod lint;
+mod page;
+mod render;
 mod repo;
+mod scaffold;
+mod stats;
+mod translation;
 mod util;
 
 use std::env;
 use std::io::{self, IsTerminal};
+use std::path::Path;
 
 use clap::Parser;
 
-use crate::args::{Cli, Commands};
+use crate::args::{Cli, Commands, Platform};
+use crate::config::Config;
 use crate::consts::MORE_INFORMATION;
 use crate::error::{Error, Result};
 use crate::repo::Repository;
-use crate::util::{cmd, spawn};
-
-fn run() -> Result<()> {
-    let cli = Cli::parse();
+use crate::util::{cmd, set_editor, spawn, warnln};
 
+/// Disables colors if they are not supported, `NO_COLOR` is set or stdout is not a terminal.
+fn init_colors() {
     #[cfg(target_os = "windows")]
     let color_support = yansi::Paint::enable_windows_ascii();
     #[cfg(not(target_os = "windows"))]
@@ -30,17 +39,95 @@ fn run() -> Result<()> {
     if !(color_support && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()) {
         yansi::Paint::disable();
     }
+}
+
+/// Loads the config and changes the current directory to the repository.
+/// Returns `None` if the repository had to be cloned first.
+fn open_repository(
+    path: Option<&Path>,
+    profile: Option<&str>,
+) -> Result<Option<(Config, Repository)>> {
+    let mut config = if let Some(config) = Config::get()? {
+        config
+    } else {
+        warnln!("the config file does not exist");
+        Repository::setup_config(path, None, false, false)?
+    };
+    let profile_applied = config.apply_profile(profile)?;
+    // A profile has its own repository, which must not be replaced by another fork's clone.
+    if profile_applied && (path.is_some() || env::var_os("TLT_REPO").is_some()) {
+        return Err(Error::Msg(
+            "'--repo' and $TLT_REPO cannot be used with a profile, which has its own repository. \
+            Use '--profile default' or set the path of the profile instead."
+                .to_string(),
+        ));
+    }
+    if let Some(editor) = &config.tools.editor {
+        set_editor(editor);
+    }
 
-    let repo = Repository::get()?;
+    let repo = Repository::new(path, &config)?;
     if !repo.ensure_exists()? {
-        // Quit if the repository did not exist.
+        return Ok(None);
+    }
+    if profile_applied {
+        repo.check_remotes()?;
+    }
+    env::set_current_dir(&repo.path)?;
+
+    Ok(Some((config, repo)))
+}
+
+/// Returns the platform for new pages: the one given on the command line, the default from the config
+/// or `common`. Only the first two have to exist.
+fn default_platform(
+    platform: Option<&Platform>,
+    config: &Config,
+    repo: &Repository,
+) -> Result<Platform> {
+    let platform = match (platform, &config.defaults.platform) {
+        (Some(platform), _) => platform.clone(),
+        (None, Some(platform)) => platform.parse().map_err(Error::Msg)?,
+        (None, None) => return Ok(Platform::default()),
+    };
+    platform.ensure_exists(&repo.path)?;
+
+    Ok(platform)
+}
+
+#[allow(clippy::too_many_lines)]
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+    init_colors();
+
+    if let Commands::Setup {
+        fork,
+        path,
+        https,
+        yes,
+    } = &cli.command
+    {
+        let path = path.as_deref().or(cli.repo.as_deref());
+        Repository::setup_config(path, fork.as_deref(), *https, *yes)?;
         return Ok(());
     }
 
-    env::set_current_dir(&repo.path)?;
+    if let Commands::Profile { command } = cli.command {
+        return commands::profile(command);
+    }
 
-    let platform = cli.platform.unwrap_or_default();
-    let language = cli.language.unwrap_or("en".to_string());
+    let Some((config, repo)) = open_repository(cli.repo.as_deref(), cli.profile.as_deref())? else {
+        // Quit if the repository did not exist.
+        return Ok(());
+    };
+
+    // Commands that operate on existing pages search all platforms if none is specified.
+    let platform = cli.platform.as_ref();
+    let explicit_language = cli.language.clone();
+    let language = cli
+        .language
+        .or(config.defaults.language.clone())
+        .unwrap_or("en".to_string());
 
     if !MORE_INFORMATION.contains_key(language.as_str()) {
         return Err(Error::Msg(format!(
@@ -50,24 +137,93 @@ fn run() -> Result<()> {
 
     match cli.command {
         Commands::Run(args) => spawn(cmd!(&args[0]).args(&args[1..]))?,
-        Commands::Destroy => commands::destroy(&repo.path)?,
+        Commands::Destroy => commands::destroy(&repo.path, repo.is_managed())?,
         Commands::Update => commands::update()?,
-        Commands::View { page } => commands::view(&repo.path, &page, &language, &platform)?,
-        Commands::Edit { page } => commands::edit(&repo.path, &page, &language, &platform)?,
+        Commands::View { page, external } => {
+            commands::view(
+                &repo.path,
+                &page,
+                external,
+                config.tools.renderer.as_deref(),
+                &language,
+                platform,
+            )?;
+        }
+        Commands::Edit { page } => commands::edit(&repo.path, &page, &language, platform)?,
         Commands::Branch { branch } => commands::branch(branch)?,
-        Commands::PullRequest => commands::pull_request(&repo.fork)?,
-        Commands::Add { page, doc_url } => {
-            commands::add(&repo.path, &page, &doc_url, &language, &platform)?;
+        Commands::PullRequest => commands::pull_request(&repo.fork_web_url())?,
+        Commands::Add {
+            page,
+            doc_url,
+            from_help,
+            from_page_help,
+            from_man,
+            from_page_man,
+        } => {
+            let from_help = from_help.or_else(|| from_page_help.then(|| page.clone()));
+            let from_man = from_man.or_else(|| from_page_man.then(|| page.clone()));
+            commands::add(
+                &repo.path,
+                &page,
+                &doc_url,
+                &config.doc_hosts,
+                from_help.as_deref(),
+                from_man.as_deref(),
+                &language,
+                &default_platform(platform, &config, &repo)?,
+            )?;
         }
         Commands::Alias { new_page, alias_of } => {
-            commands::alias(&repo.path, &new_page, &alias_of, &language, &platform)?;
+            commands::alias(
+                &repo.path,
+                &new_page,
+                &alias_of,
+                &language,
+                &default_platform(platform, &config, &repo)?,
+            )?;
+        }
+        Commands::Translate { page, marker } => {
+            commands::translate(&repo.path, &page, &marker, &language, platform)?;
+        }
+        Commands::Lint { pages } => commands::lint(&repo.path, &pages, &language, platform)?,
+        Commands::Format { page, check } => {
+            commands::format(&repo.path, &page, check, &language, platform)?;
+        }
+        Commands::Outdated => commands::outdated(&repo.path, &language)?,
+        Commands::Stats { missing, json } => {
+            commands::stats(&repo.path, missing, json, &language)?;
+        }
+        Commands::Compare { page } => commands::compare(&repo.path, &page, &language, platform)?,
+        Commands::Untranslated { pages, marker } => {
+            commands::untranslated(&repo.path, &pages, &marker, &language, platform)?;
+        }
+        Commands::Search {
+            pattern,
+            ignore_case,
+            all_languages,
+            scope,
+        } => {
+            commands::search(
+                &repo.path,
+                &pattern,
+                ignore_case,
+                all_languages,
+                scope,
+                &language,
+                platform,
+            )?;
         }
-        Commands::Translate { page } => {
-            commands::translate(&repo.path, &page, &language, &platform)?;
+        Commands::List { filters, format } => {
+            commands::list(&repo.path, &filters, format, &language, platform)?;
         }
-        Commands::Setup => {
-            Repository::setup_config()?;
+        Commands::Rename { old, new } => commands::rename(&repo.path, &old, &new)?,
+        Commands::Move { page, from, to } => commands::move_page(&repo.path, &page, &from, &to)?,
+        Commands::Remove { page } => {
+            commands::remove(&repo.path, &page, explicit_language.as_deref(), platform)?;
         }
+        Commands::Remotes { repair } => commands::remotes(&repo, repair)?,
+        // Handled before opening the repository.
+        Commands::Setup { .. } | Commands::Profile { .. } => unreachable!(),
     }
 
     Ok(())
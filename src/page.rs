@@ -0,0 +1,364 @@
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// A part of a command line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    /// Text that is meant to be typed as-is.
+    Text(String),
+    /// The contents of a `{{placeholder}}`.
+    Placeholder(String),
+}
+
+/// Checks if `s` contains more opening than closing braces.
+fn is_unbalanced(s: &str) -> bool {
+    s.matches('{').count() > s.matches('}').count()
+}
+
+/// A command line of an example, split into text and placeholders.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Command(pub Vec<Token>);
+
+impl Command {
+    /// Splits the contents of a command line (without backticks) into tokens.
+    pub fn parse(s: &str) -> Self {
+        let mut tokens = vec![];
+        let mut rest = s;
+
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            // Include the closing braces of braces opened in the placeholder, e.g. `{{a{b}}}`.
+            let mut end = start + 2 + len;
+            while is_unbalanced(&rest[start + 2..end]) && rest[end + 2..].starts_with('}') {
+                end += 1;
+            }
+
+            if start != 0 {
+                tokens.push(Token::Text(rest[..start].to_string()));
+            }
+            tokens.push(Token::Placeholder(rest[start + 2..end].to_string()));
+            rest = &rest[end + 2..];
+        }
+
+        if !rest.is_empty() {
+            tokens.push(Token::Text(rest.to_string()));
+        }
+
+        Self(tokens)
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in &self.0 {
+            match token {
+                Token::Text(s) => write!(f, "{s}")?,
+                Token::Placeholder(s) => write!(f, "{{{{{s}}}}}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The "More information" link of a page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoreInformation {
+    /// The label, e.g. `More information` (translated in non-English pages).
+    pub label: String,
+    pub url: String,
+}
+
+/// An example consisting of a description (without the leading `- `) and a command line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub description: String,
+    pub command: Command,
+}
+
+/// A parsed tldr page.
+///
+/// Serializing a page with `Display` produces the canonical page format,
+/// so a well-formed page is reproduced byte-for-byte.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Page {
+    pub title: String,
+    /// Description lines (without the leading `> `), excluding the "More information" link.
+    pub description: Vec<String>,
+    pub more_information: Option<MoreInformation>,
+    pub examples: Vec<Example>,
+}
+
+/// Tries to split a description line into a "More information" label and URL.
+fn parse_more_information(line: &str) -> Option<MoreInformation> {
+    let line = line.strip_suffix('.').unwrap_or(line).strip_suffix('>')?;
+    let (label, url) = line.rsplit_once(": <")?;
+
+    Some(MoreInformation {
+        label: label.to_string(),
+        url: url.to_string(),
+    })
+}
+
+impl Page {
+    /// Parses a page. Blank lines and trailing whitespace are ignored.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut page = Self::default();
+        let mut title = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim_end();
+            let lnum = i + 1;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(s) = line.strip_prefix('#') {
+                if title.is_some() {
+                    return Err(Error::Msg(format!("line {lnum}: duplicate title")));
+                }
+                title = Some(s.trim_start().to_string());
+            } else if let Some(s) = line.strip_prefix('>') {
+                page.description.push(s.trim_start().to_string());
+            } else if let Some(s) = line.strip_prefix('-') {
+                page.examples.push(Example {
+                    description: s.trim_start().to_string(),
+                    command: Command::default(),
+                });
+            } else if line.starts_with('`') {
+                let Some(example) = page.examples.last_mut() else {
+                    return Err(Error::Msg(format!(
+                        "line {lnum}: command line without a description"
+                    )));
+                };
+                let s = line.strip_prefix('`').unwrap();
+                example.command = Command::parse(s.strip_suffix('`').unwrap_or(s));
+            } else {
+                return Err(Error::Msg(format!("line {lnum}: unexpected text '{line}'")));
+            }
+        }
+
+        // Only the last description line is the "More information" link, so that
+        // a link in any other position is kept where it is.
+        if let Some(info) = page
+            .description
+            .last()
+            .and_then(|l| parse_more_information(l))
+        {
+            page.description.pop();
+            page.more_information = Some(info);
+        }
+
+        page.title = title.ok_or_else(|| Error::Msg("the page has no title".to_string()))?;
+        Ok(page)
+    }
+
+    /// Fixes punctuation and whitespace that the canonical format does not take care of.
+    pub fn normalize(&mut self) {
+        self.title = self.title.trim().to_string();
+
+        self.description.retain(|line| !line.trim().is_empty());
+        for line in &mut self.description {
+            *line = line.trim().to_string();
+            if !line.ends_with('.') {
+                line.push('.');
+            }
+        }
+
+        for example in &mut self.examples {
+            let description = example.description.trim();
+            let description = description.trim_end_matches(['.', ',', ';', ':']);
+            example.description = format!("{}:", description.trim_end());
+
+            let tokens = &mut example.command.0;
+            if let Some(Token::Text(s)) = tokens.first_mut() {
+                *s = s.trim_start().to_string();
+            }
+            if let Some(Token::Text(s)) = tokens.last_mut() {
+                *s = s.trim_end().to_string();
+            }
+            tokens.retain(|token| token != &Token::Text(String::new()));
+        }
+    }
+
+    /// Returns the name of the original command if this is an alias page (in any language),
+    /// i.e. a page with a single description line mentioning `` `command` ``
+    /// and a single example running `tldr command`.
+    pub fn alias_of(&self) -> Option<&str> {
+        let ([line], [example]) = (self.description.as_slice(), self.examples.as_slice()) else {
+            return None;
+        };
+        let [Token::Text(command)] = example.command.0.as_slice() else {
+            return None;
+        };
+
+        let original = command.strip_prefix("tldr ")?;
+        line.contains(&format!("`{original}`")).then_some(original)
+    }
+
+    /// Reads and parses the page at `path`.
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?).map_err(|e| e.in_file(path))
+    }
+}
+
+impl Display for Page {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# {}\n", self.title)?;
+
+        for line in &self.description {
+            writeln!(f, "> {line}")?;
+        }
+        if let Some(info) = &self.more_information {
+            writeln!(f, "> {}: <{}>.", info.label, info.url)?;
+        }
+
+        for example in &self.examples {
+            write!(f, "\n- {}\n\n`{}`\n", example.description, example.command)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "# tar
+
+> Archiving utility.
+> Often combined with a compression method, such as `gzip` or `bzip2`.
+> More information: <https://www.gnu.org/software/tar>.
+
+- [c]reate an archive and write it to a [f]ile:
+
+`tar cf {{path/to/target.tar}} {{path/to/file1 path/to/file2 ...}}`
+
+- List the contents of a tar file [v]erbosely:
+
+`tar tvf {{path/to/source.tar}}`
+";
+
+    #[test]
+    fn round_trip() {
+        let page = Page::parse(PAGE).unwrap();
+
+        assert_eq!(page.title, "tar");
+        assert_eq!(page.description.len(), 2);
+        assert_eq!(
+            page.more_information,
+            Some(MoreInformation {
+                label: "More information".to_string(),
+                url: "https://www.gnu.org/software/tar".to_string(),
+            })
+        );
+        assert_eq!(page.examples.len(), 2);
+        assert_eq!(page.to_string(), PAGE);
+    }
+
+    #[test]
+    fn round_trip_without_more_information() {
+        let s = "# true\n\n> Return a successful exit status code.\n\n- Return a successful exit code:\n\n`true`\n";
+        let page = Page::parse(s).unwrap();
+
+        assert_eq!(page.more_information, None);
+        assert_eq!(page.to_string(), s);
+    }
+
+    #[test]
+    fn more_information_not_last() {
+        let s = "# foo\n\n> More information: <https://example.com>.\n> Do foo.\n";
+        let page = Page::parse(s).unwrap();
+
+        assert_eq!(page.more_information, None);
+        assert_eq!(page.to_string(), s);
+    }
+
+    #[test]
+    fn normalize() {
+        let s = "#  tar \n>Archiving utility  \n> More information: <https://www.gnu.org/software/tar>.\n\n\n-List the contents of a tar file.\n`  tar tf {{path/to/source.tar}}  `  \n-  Extract an archive;\n\n`tar xf {{path/to/source.tar}}`";
+        let mut page = Page::parse(s).unwrap();
+        page.normalize();
+
+        assert_eq!(
+            page.to_string(),
+            "# tar
+
+> Archiving utility.
+> More information: <https://www.gnu.org/software/tar>.
+
+- List the contents of a tar file:
+
+`tar tf {{path/to/source.tar}}`
+
+- Extract an archive:
+
+`tar xf {{path/to/source.tar}}`
+"
+        );
+        assert_eq!(Page::parse(&page.to_string()).unwrap(), page);
+    }
+
+    #[test]
+    fn placeholders() {
+        use Token::{Placeholder, Text};
+
+        assert_eq!(
+            Command::parse("ls {{path}} -l").0,
+            [
+                Text("ls ".to_string()),
+                Placeholder("path".to_string()),
+                Text(" -l".to_string())
+            ]
+        );
+        assert_eq!(
+            Command::parse("echo {{a{b}}}").0,
+            [Text("echo ".to_string()), Placeholder("a{b}".to_string())]
+        );
+        assert_eq!(
+            Command::parse("echo {{}}}").0,
+            [
+                Text("echo ".to_string()),
+                Placeholder(String::new()),
+                Text("}".to_string())
+            ]
+        );
+        assert_eq!(
+            Command::parse("echo {{unclosed").0,
+            [Text("echo {{unclosed".to_string())]
+        );
+
+        for s in [
+            "echo {{a{b}}}",
+            "echo {{}}}",
+            r"find {{.}} -exec {{cmd}} {} \;",
+        ] {
+            assert_eq!(Command::parse(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn alias_page() {
+        let page = Page::parse(
+            "# vi\n\n> This command is an alias of `vim`.\n\n- View documentation for the original command:\n\n`tldr vim`\n",
+        )
+        .unwrap();
+
+        assert_eq!(page.alias_of(), Some("vim"));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Page::parse("> No title.\n").is_err());
+        assert!(Page::parse("# a\n# b\n").is_err());
+        assert!(Page::parse("# a\n\n`command`\n").is_err());
+        assert!(Page::parse("# a\n\nstray text\n").is_err());
+    }
+}
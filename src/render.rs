@@ -0,0 +1,47 @@
+use std::io::{self, Write};
+
+use yansi::{Color, Paint};
+
+use crate::page::{Page, Token};
+
+/// Renders a page to stdout in a similar style to tldr clients.
+///
+/// Placeholders are underlined, or shown with their braces if colors are disabled.
+pub fn render(page: &Page) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    writeln!(stdout, "\n  {}\n", Paint::new(&page.title).bold())?;
+
+    for line in &page.description {
+        writeln!(stdout, "  {line}")?;
+    }
+    if let Some(info) = &page.more_information {
+        writeln!(
+            stdout,
+            "  {}: {}",
+            info.label,
+            Paint::new(&info.url).fg(Color::Blue).underline()
+        )?;
+    }
+
+    for example in &page.examples {
+        write!(
+            stdout,
+            "\n  {}\n\n      ",
+            Paint::new(format!("- {}", example.description)).fg(Color::Green)
+        )?;
+
+        for token in &example.command.0 {
+            match token {
+                Token::Text(s) => write!(stdout, "{}", Paint::new(s).fg(Color::Red))?,
+                Token::Placeholder(s) if Paint::is_enabled() => {
+                    write!(stdout, "{}", Paint::new(s).fg(Color::Red).underline())?;
+                }
+                Token::Placeholder(s) => write!(stdout, "{{{{{s}}}}}")?,
+            }
+        }
+        writeln!(stdout)?;
+    }
+
+    writeln!(stdout)
+}
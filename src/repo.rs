@@ -1,13 +1,27 @@
-use std::fs::{self, File};
-use std::io::{self, Write};
-use std::path::PathBuf;
-
-use crate::error::{Error, Result};
-use crate::util::{cmd, infoln, spawn, warnln};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::config::{Config, Scheme};
+use crate::error::Result;
+use crate::util::{absolute, cmd, confirm, infoln, spawn, warnln};
+
+/// The default git host.
+const HOST: &str = "github.com";
+
+/// The state of a git remote compared to the config.
+pub enum RemoteStatus {
+    Ok,
+    Missing,
+    /// The remote points to a different URL.
+    Mismatch(String),
+}
 
 pub struct Repository {
     pub path: PathBuf,
     pub fork: String,
+    scheme: Scheme,
+    host: String,
+    upstream: String,
 }
 
 impl Repository {
@@ -15,28 +29,133 @@ impl Repository {
         dirs::cache_dir().unwrap().join(env!("CARGO_PKG_NAME"))
     }
 
-    pub fn locate_config() -> PathBuf {
-        dirs::config_dir()
-            .unwrap()
-            .join(env!("CARGO_PKG_NAME"))
-            .join("fork")
+    /// Returns the default location of the repository of the profile `name`,
+    /// so that profiles with different forks do not share a clone.
+    pub fn locate_profile(name: &str) -> PathBuf {
+        Self::locate().with_file_name(format!("{}-{name}", env!("CARGO_PKG_NAME")))
+    }
+
+    /// Chooses the path of the repository: `path` (from `--repo`), `$TLT_REPO`,
+    /// the path from the config file or the cache directory, in this order.
+    /// A relative path is made absolute, as tlt changes the current directory to the repository.
+    fn choose_path(path: Option<&Path>, config: &Config) -> Result<PathBuf> {
+        absolute(
+            &path
+                .map(Path::to_path_buf)
+                .or_else(|| env::var_os("TLT_REPO").map(PathBuf::from))
+                .or_else(|| config.repository.path.clone())
+                .unwrap_or_else(Self::locate),
+        )
+    }
+
+    pub fn new(path: Option<&Path>, config: &Config) -> Result<Self> {
+        let host = config.repository.host.as_deref().unwrap_or(HOST);
+
+        Ok(Self {
+            path: Self::choose_path(path, config)?,
+            fork: config.repository.fork.clone(),
+            scheme: config.repository.scheme,
+            host: host.to_string(),
+            upstream: config
+                .repository
+                .upstream
+                .clone()
+                .unwrap_or(format!("https://{host}/tldr-pages/tldr.git")),
+        })
+    }
+
+    fn fork_url(&self) -> String {
+        match self.scheme {
+            Scheme::Ssh => format!("git@{}:{}.git", self.host, self.fork),
+            Scheme::Https => format!("https://{}/{}.git", self.host, self.fork),
+        }
+    }
+
+    /// Returns the URL of the fork's web page.
+    pub fn fork_web_url(&self) -> String {
+        format!("https://{}/{}", self.host, self.fork)
+    }
+
+    /// Returns the names of the remotes and the URLs they should point to.
+    pub fn remotes(&self) -> [(&'static str, String); 2] {
+        [
+            ("origin", self.fork_url()),
+            ("upstream", self.upstream.clone()),
+        ]
+    }
+
+    pub fn remote_status(&self, name: &str, expected: &str) -> Result<RemoteStatus> {
+        let output = cmd!("git", "-C", &self.path, "remote", "get-url", name).output()?;
+
+        if !output.status.success() {
+            return Ok(RemoteStatus::Missing);
+        }
+
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(if url == expected {
+            RemoteStatus::Ok
+        } else {
+            RemoteStatus::Mismatch(url)
+        })
+    }
+
+    /// Adds the remote `name` or changes its URL.
+    pub fn set_remote(&self, name: &str, url: &str, status: &RemoteStatus) -> Result<()> {
+        let action = if let RemoteStatus::Missing = status {
+            "add"
+        } else {
+            "set-url"
+        };
+
+        spawn(&mut cmd!(
+            "git", "-C", &self.path, "remote", action, name, url
+        ))
     }
 
     fn exists(&self) -> bool {
         self.path.join("pages").exists()
     }
 
+    /// Returns `true` if the repository is in the cache directory (including the default locations
+    /// of profiles), i.e. it was cloned by tlt. Other repositories are never deleted.
+    pub fn is_managed(&self) -> bool {
+        let prefix = concat!(env!("CARGO_PKG_NAME"), "-");
+        self.path == Self::locate()
+            || (self.path.parent() == Self::locate().parent()
+                && self
+                    .path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(prefix)))
+    }
+
+    /// Reuses an existing clone of tldr, adding missing remotes.
+    fn adopt(&self) -> Result<()> {
+        infoln!("using the existing repository at '{}'", self.path.display());
+        self.check_remotes()
+    }
+
+    /// Adds missing remotes and warns about the ones that do not point to the URLs from the config file.
+    pub fn check_remotes(&self) -> Result<()> {
+        for (name, url) in self.remotes() {
+            match self.remote_status(name, &url)? {
+                RemoteStatus::Ok => {}
+                RemoteStatus::Missing => self.set_remote(name, &url, &RemoteStatus::Missing)?,
+                RemoteStatus::Mismatch(actual) => warnln!(
+                    "the '{name}' remote points to '{actual}' instead of '{url}'. Run 'tlt remotes --repair' to fix it."
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn ensure_exists(&self) -> Result<bool> {
         if self.exists() {
             Ok(true)
         } else {
             warnln!("'{}': not a directory", self.path.join("pages").display());
-            spawn(&mut cmd!(
-                "git",
-                "clone",
-                format!("git@github.com:{}.git", self.fork),
-                &self.path
-            ))?;
+            spawn(&mut cmd!("git", "clone", self.fork_url(), &self.path))?;
 
             spawn(&mut cmd!(
                 "git",
@@ -45,68 +164,51 @@ impl Repository {
                 "remote",
                 "add",
                 "upstream",
-                "https://github.com/tldr-pages/tldr.git"
+                &self.upstream
             ))?;
 
             Ok(false)
         }
     }
 
-    fn write_config(&self) -> Result<()> {
-        let location = Self::locate_config();
-        fs::create_dir_all(location.parent().unwrap())?;
-
-        write!(
-            File::create(location)?,
-            "DO NOT EDIT THIS FILE.\n\
-        If you want to change your repository, run 'tlt setup' instead.\n{}",
-            self.fork
-        )
-        .map_err(|e| Error::Msg(format!("failed to write to the config file: {e}")))?;
-
-        Ok(())
-    }
-
-    pub fn setup_config() -> Result<Self> {
-        let mut fork = String::new();
-        writeln!(
-            io::stdout(),
-            "Please enter the github path to your fork (username/repo_name): "
-        )?;
-        io::stdout().flush()?;
-        io::stdin().read_line(&mut fork)?;
-
-        let repo = Self {
-            path: Self::locate(),
-            fork: fork.strip_suffix('\n').unwrap().to_string(),
-        };
-        repo.write_config()?;
-
-        if repo.exists() {
-            infoln!(
-                "deleting the old repository. The new one will be cloned the next time you run 'tlt'."
-            );
-            fs::remove_dir_all(&repo.path)?;
+    /// Recreates the config file. If `path` (or `$TLT_REPO`) points to an existing clone of tldr,
+    /// it is saved in the config file and reused. The fork is prompted for if not given.
+    /// The old repository in the cache directory is deleted after confirmation, or if `yes` is `true`.
+    pub fn setup_config(
+        path: Option<&Path>,
+        fork: Option<&str>,
+        https: bool,
+        yes: bool,
+    ) -> Result<Config> {
+        let mut config = Config::setup(fork, https)?;
+        let repo = Self::new(path, &config)?;
+
+        if !repo.is_managed() {
+            config.repository.path = Some(repo.path.clone());
+            config.write()?;
         }
 
-        Ok(repo)
-    }
-
-    pub fn get() -> Result<Self> {
-        let location = Self::locate_config();
-
-        if !location.is_file() {
-            warnln!("the config file does not exist");
-            let repo = Self::setup_config()?;
-
-            return Ok(repo);
+        if !repo.exists() {
+            if !repo.is_managed() {
+                infoln!(
+                    "the repository will be cloned to '{}' the next time you run 'tlt'.",
+                    repo.path.display()
+                );
+            }
+        } else if repo.is_managed() {
+            let question = format!("Delete the old repository at '{}'?", repo.path.display());
+            if yes || confirm(&question)? {
+                infoln!(
+                    "deleting the old repository. The new one will be cloned the next time you run 'tlt'."
+                );
+                fs::remove_dir_all(&repo.path)?;
+            } else {
+                infoln!("keeping the old repository. Run 'tlt remotes --repair' to use the new fork in it.");
+            }
+        } else {
+            repo.adopt()?;
         }
 
-        let file_contents = fs::read_to_string(location)?;
-
-        Ok(Self {
-            path: Self::locate(),
-            fork: file_contents.lines().last().unwrap().to_string(),
-        })
+        Ok(config)
     }
 }
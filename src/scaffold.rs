@@ -0,0 +1,657 @@
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::consts::MORE_INFORMATION;
+use crate::error::{Error, Result};
+use crate::page::{Command, Example, MoreInformation, Page, Token};
+use crate::util::cmd;
+
+/// The maximum number of examples in a tldr page.
+const MAX_EXAMPLES: usize = 8;
+
+/// Matches the short option, long option and argument of an option, e.g. `-f, --file=FILE`.
+const FLAGS: &str =
+    r"(-[[:alnum:]])?(?:,?\s*(--[[:alnum:]][\w-]*))?(?:[= ]?(<[^>]+>|\[=?[^\]]+\]|[A-Z][A-Z_]*))?";
+
+/// Matches an option in help text, e.g. `  -f, --file=FILE   read FILE`.
+static OPTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"^\s+{FLAGS}(?:\s{{2,}}|\t)(\S.*)$")).unwrap());
+
+/// Matches an option in a formatted man page. The description may be on the same line.
+static MAN_OPTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"^\s+{FLAGS}(?:\s+(\S.*))?$")).unwrap());
+
+/// Matches a character overstruck with backspaces, used by `man` for bold and underlined text.
+static OVERSTRIKE: Lazy<Regex> = Lazy::new(|| Regex::new(r".\x08").unwrap());
+
+/// Matches font changes and other inline roff escapes that do not produce any text,
+/// e.g. the italic corrections `\,` and `\/` added by help2man.
+static ROFF_ESCAPE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\\f(\(..|\[[^\]]*\]|.)|\\[&%,/:|^]").unwrap());
+
+/// A command-line option found in help text or a man page.
+pub struct HelpOption {
+    pub short: Option<String>,
+    pub long: Option<String>,
+    pub argument: Option<String>,
+    pub description: String,
+}
+
+impl HelpOption {
+    fn is_help_or_version(&self) -> bool {
+        matches!(self.long.as_deref(), Some("--help" | "--version"))
+            || matches!(self.short.as_deref(), Some("-h"))
+    }
+}
+
+/// Finds options in `text`, in order. Descriptions wrapped onto the following lines are joined,
+/// and so are descriptions starting on the line after the option, as in clap's long help.
+pub fn parse_options(text: &str) -> Vec<HelpOption> {
+    let mut options: Vec<HelpOption> = vec![];
+    let mut continued = false;
+
+    for line in text.lines() {
+        let has_flags = |caps: &Captures| caps.get(1).is_some() || caps.get(2).is_some();
+        let caps = OPTION.captures(line).filter(has_flags).or_else(|| {
+            MAN_OPTION
+                .captures(line)
+                .filter(|caps| has_flags(caps) && caps.get(4).is_none())
+        });
+
+        if let Some(caps) = caps {
+            options.push(HelpOption {
+                short: caps.get(1).map(|m| m.as_str().to_string()),
+                long: caps.get(2).map(|m| m.as_str().to_string()),
+                argument: caps.get(3).map(|m| m.as_str().to_string()),
+                description: caps.get(4).map_or("", |m| m.as_str().trim()).to_string(),
+            });
+            continued = true;
+        } else if continued
+            && line.starts_with(char::is_whitespace)
+            && !line.trim().is_empty()
+            && !line.trim_start().starts_with('-')
+        {
+            let option = options.last_mut().unwrap();
+            if !option.description.is_empty() {
+                option.description.push(' ');
+            }
+            option.description.push_str(line.trim());
+        } else {
+            continued = false;
+        }
+    }
+
+    options.retain(|option| !option.description.is_empty());
+    options
+}
+
+/// Capitalizes the first letter of `s`.
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or(String::new(), |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Turns a sentence into a page description line, i.e. capitalized and ending with a period.
+pub fn sentence(s: &str) -> String {
+    let s = capitalize(s.trim().trim_end_matches(['.', ':', ';', ',']).trim_end());
+    format!("{s}.")
+}
+
+/// Turns an option argument like `<FILE>` or `[=WHEN]` into placeholder contents.
+fn placeholder(argument: &str) -> String {
+    let name = argument
+        .trim_matches(['<', '>', '[', ']', '='])
+        .to_lowercase()
+        .replace(['_', ' '], "-");
+
+    match name.as_str() {
+        "file" | "filename" | "path" => "path/to/file".to_string(),
+        "dir" | "directory" => "path/to/directory".to_string(),
+        _ => name,
+    }
+}
+
+/// Creates an example stub for an option.
+fn example(command: &str, option: &HelpOption) -> Example {
+    let flag = match (&option.short, &option.long) {
+        (Some(short), Some(long)) => format!("[{short}|{long}]"),
+        (Some(flag), None) | (None, Some(flag)) => flag.clone(),
+        (None, None) => unreachable!(),
+    };
+
+    let mut tokens = vec![Token::Text(format!("{command} ")), Token::Placeholder(flag)];
+    if let Some(argument) = &option.argument {
+        tokens.push(Token::Text(" ".to_string()));
+        tokens.push(Token::Placeholder(placeholder(argument)));
+    }
+
+    Example {
+        description: format!("{}:", sentence(&option.description).trim_end_matches('.')),
+        command: Command(tokens),
+    }
+}
+
+/// Creates a page with example stubs for up to eight of `options`.
+pub fn page(
+    command: &str,
+    description: Option<String>,
+    options: &[HelpOption],
+    doc_url: &str,
+    language: &str,
+) -> Page {
+    Page {
+        title: command.to_string(),
+        description: description.into_iter().collect(),
+        more_information: Some(MoreInformation {
+            label: MORE_INFORMATION[language].to_string(),
+            url: doc_url.to_string(),
+        }),
+        examples: options
+            .iter()
+            .filter(|option| !option.is_help_or_version())
+            .take(MAX_EXAMPLES)
+            .map(|option| example(command, option))
+            .collect(),
+    }
+}
+
+/// Extracts the description of a command from its `--help` output:
+/// the first line which is not a part of the usage, an option or a section header.
+pub fn help_description(help: &str) -> Option<String> {
+    let mut in_usage = false;
+
+    for line in help.lines() {
+        if line.to_lowercase().trim_start().starts_with("usage") {
+            in_usage = true;
+            continue;
+        }
+        if line.trim().is_empty() {
+            in_usage = false;
+            continue;
+        }
+        if in_usage && line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        in_usage = false;
+
+        let line = line.trim();
+        if line.starts_with('-') || line.ends_with(':') || OPTION.is_match(line) {
+            continue;
+        }
+        return Some(sentence(line));
+    }
+
+    None
+}
+
+/// Returns the `--help` output of `command`, or reads help text from stdin if `command` is `-`.
+/// Help is taken from stderr if the command does not print anything to stdout.
+pub fn help_text(command: &str) -> Result<String> {
+    if command == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        return Ok(text);
+    }
+
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| Error::Msg("no command given to get help from".to_string()))?;
+
+    let output = cmd!(program)
+        .args(words)
+        .arg("--help")
+        .output()
+        .map_err(|e| Error::Msg(format!("cannot run '{command} --help': {e}")))?;
+
+    let text = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    let text = String::from_utf8_lossy(&text).into_owned();
+
+    if text.trim().is_empty() {
+        return Err(Error::Msg(format!("'{command} --help' printed nothing")));
+    }
+    Ok(text)
+}
+
+/// Returns a man page formatted as plain text. `command` can also be a path to a roff source file.
+/// If `man` is not installed, the roff source is looked up in the local man path instead.
+pub fn man_text(command: &str) -> Result<String> {
+    if command.contains('/') {
+        return Ok(roff_to_text(&read_roff(Path::new(command))?));
+    }
+
+    match cmd!("man", "-P", "cat", command)
+        .env("MANWIDTH", "80")
+        .env_remove("MAN_KEEP_FORMATTING")
+        .output()
+    {
+        Ok(output) if output.status.success() && !output.stdout.is_empty() => {
+            let text = String::from_utf8_lossy(&output.stdout);
+            Ok(OVERSTRIKE.replace_all(&text, "").into_owned())
+        }
+        Ok(_) => Err(Error::Msg(format!("no man page for '{command}'"))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let path = find_roff(command)
+                .ok_or_else(|| Error::Msg(format!("no man page for '{command}'")))?;
+            Ok(roff_to_text(&read_roff(&path)?))
+        }
+        Err(e) => Err(Error::Msg(format!("cannot run 'man {command}': {e}"))),
+    }
+}
+
+/// Finds the roff source of the man page of `command` in `MANPATH` or the default man directories.
+fn find_roff(command: &str) -> Option<PathBuf> {
+    let man_path =
+        env::var("MANPATH").unwrap_or_else(|_| "/usr/local/share/man:/usr/share/man".to_string());
+
+    env::split_paths(&man_path)
+        .flat_map(|dir| {
+            ["1", "8", "6"].into_iter().flat_map(move |section| {
+                let file = dir.join(format!("man{section}/{command}.{section}"));
+                [file.with_extension(format!("{section}.gz")), file]
+            })
+        })
+        .find(|path| path.is_file())
+}
+
+/// Reads a roff source file, decompressing it with `gzip` if needed.
+fn read_roff(path: &Path) -> Result<String> {
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let output = cmd!("gzip", "-dc", path).output()?;
+        if !output.status.success() {
+            return Err(Error::ChildProcess(output.status.to_string()));
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    fs::read_to_string(path)
+        .map_err(|e| Error::Msg(format!("cannot read '{}': {e}", path.display())))
+}
+
+/// Converts roff text to plain text. Only the few man macros needed to find the
+/// NAME and OPTIONS sections are supported, laid out like `man` would format them.
+fn roff_to_text(roff: &str) -> String {
+    let inline = |s: &str| {
+        ROFF_ESCAPE
+            .replace_all(s, "")
+            .replace("\\-", "-")
+            .replace("\\(em", "—")
+            .replace("\\(aq", "'")
+            .replace("\\(dq", "\"")
+            .replace("\\(co", "©")
+            .replace("\\ ", " ")
+            .replace("\\e", "\\")
+    };
+
+    let mut text = vec![];
+    let mut indent = 7;
+    let mut tag_next = false;
+
+    for line in roff.lines() {
+        let line = if let Some(request) = line.strip_prefix('.') {
+            let (name, args) = request
+                .split_once(char::is_whitespace)
+                .unwrap_or((request, ""));
+            match name {
+                "SH" => {
+                    text.push(String::new());
+                    text.push(args.trim_matches('"').to_string());
+                    indent = 7;
+                    continue;
+                }
+                "SS" => {
+                    text.push(String::new());
+                    text.push(format!("   {}", args.trim_matches('"')));
+                    indent = 7;
+                    continue;
+                }
+                "TP" => {
+                    text.push(String::new());
+                    indent = 7;
+                    tag_next = true;
+                    continue;
+                }
+                "PP" | "P" | "LP" | "IP" => {
+                    text.push(String::new());
+                    indent = 7;
+                    continue;
+                }
+                // Font macros; alternating ones like `.BR` join their arguments.
+                "B" | "I" | "SM" => args.trim_matches('"').to_string(),
+                "BR" | "BI" | "IB" | "IR" | "RB" | "RI" => args
+                    .split_whitespace()
+                    .map(|arg| arg.trim_matches('"'))
+                    .collect(),
+                _ => continue,
+            }
+        } else if line.starts_with('\'') {
+            continue;
+        } else {
+            line.to_string()
+        };
+
+        text.push(format!("{:indent$}{}", "", inline(&line)));
+        if tag_next {
+            tag_next = false;
+            indent = 14;
+        }
+    }
+
+    text.join("\n")
+}
+
+/// Returns the lines of a section of a formatted man page, or `None` if it does not exist.
+fn man_section<'a>(text: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let mut lines = text.lines().skip_while(|line| line.trim_end() != name);
+    lines.next()?;
+
+    Some(
+        lines
+            .take_while(|line| line.is_empty() || line.starts_with(char::is_whitespace))
+            .collect(),
+    )
+}
+
+/// Extracts the description of a command from its man page: the summary in NAME,
+/// or the first sentence of DESCRIPTION.
+pub fn man_description(text: &str) -> Option<String> {
+    let name = man_section(text, "NAME").unwrap_or_default().join(" ");
+    if let Some((_, summary)) = name.split_once(" - ").or_else(|| name.split_once(" — ")) {
+        return Some(sentence(summary));
+    }
+
+    let description = man_section(text, "DESCRIPTION")?
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let first = description.split(". ").next()?;
+    (!first.is_empty()).then(|| sentence(first))
+}
+
+/// Finds options in the OPTIONS section of a man page, or in DESCRIPTION if there is none.
+/// Only the first paragraph of each option's description is kept.
+pub fn man_options(text: &str) -> Vec<HelpOption> {
+    let Some(lines) = man_section(text, "OPTIONS").or_else(|| man_section(text, "DESCRIPTION"))
+    else {
+        return vec![];
+    };
+
+    let mut options: Vec<HelpOption> = vec![];
+    // The indentation of the current option, while its description is being read.
+    let mut option_indent = None;
+
+    for line in lines {
+        let indent = line.len() - line.trim_start().len();
+        let caps = MAN_OPTION
+            .captures(line)
+            .filter(|caps| caps.get(1).is_some() || caps.get(2).is_some());
+
+        if option_indent.is_some_and(|option_indent| indent > option_indent) {
+            let description = &mut options.last_mut().unwrap().description;
+            // Words hyphenated by man at the end of a line are joined again.
+            let hyphenated = description
+                .strip_suffix('-')
+                .is_some_and(|s| s.ends_with(char::is_alphanumeric));
+            if !description.is_empty() && !hyphenated {
+                description.push(' ');
+            }
+            description.push_str(line.trim());
+        } else if let Some(caps) = caps {
+            options.push(HelpOption {
+                short: caps.get(1).map(|m| m.as_str().to_string()),
+                long: caps.get(2).map(|m| m.as_str().to_string()),
+                argument: caps.get(3).map(|m| m.as_str().to_string()),
+                description: caps.get(4).map_or("", |m| m.as_str().trim()).to_string(),
+            });
+            option_indent = Some(indent);
+        } else if !line.trim().is_empty()
+            || options
+                .last()
+                .is_some_and(|option| !option.description.is_empty())
+        {
+            option_indent = None;
+        }
+    }
+
+    options.retain(|option| !option.description.is_empty());
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `ls --help` from GNU coreutils 9.1.
+    const LS_HELP: &str = include_str!("../tests/fixtures/ls-help.txt");
+    /// `tlt list --help`, a clap program printing descriptions below the options.
+    const CLAP_HELP: &str = include_str!("../tests/fixtures/tlt-list-help.txt");
+    /// The roff source of the man page of GNU ls 9.1, generated by help2man.
+    const LS_ROFF: &str = include_str!("../tests/fixtures/ls.1");
+    /// Parts of the same man page, as formatted by `man -P cat` with `MANWIDTH=80`.
+    const LS_MAN: &str = include_str!("../tests/fixtures/ls.1.txt");
+
+    fn find<'a>(options: &'a [HelpOption], long: &str) -> &'a HelpOption {
+        options
+            .iter()
+            .find(|option| option.long.as_deref() == Some(long))
+            .unwrap()
+    }
+
+    #[test]
+    fn gnu_options() {
+        let options = parse_options(LS_HELP);
+
+        let all = &options[0];
+        assert_eq!(all.short.as_deref(), Some("-a"));
+        assert_eq!(all.long.as_deref(), Some("--all"));
+        assert_eq!(all.argument, None);
+        assert_eq!(all.description, "do not ignore entries starting with .");
+
+        let block_size = find(&options, "--block-size");
+        assert_eq!(block_size.short, None);
+        assert_eq!(block_size.argument.as_deref(), Some("SIZE"));
+        assert_eq!(
+            block_size.description,
+            "with -l, scale sizes by SIZE when printing them; e.g., '--block-size=M'; see SIZE format below"
+        );
+
+        assert_eq!(
+            find(&options, "--color").argument.as_deref(),
+            Some("[=WHEN]")
+        );
+
+        let ctime = options
+            .iter()
+            .find(|option| option.short.as_deref() == Some("-c"))
+            .unwrap();
+        assert_eq!(ctime.long, None);
+        assert!(ctime
+            .description
+            .ends_with("otherwise: sort by ctime, newest first"));
+
+        assert!(options.iter().any(HelpOption::is_help_or_version));
+    }
+
+    #[test]
+    fn clap_options() {
+        let options = parse_options(CLAP_HELP);
+        let longs: Vec<&str> = options.iter().filter_map(|o| o.long.as_deref()).collect();
+
+        assert_eq!(
+            longs,
+            [
+                "--aliases",
+                "--untranslated",
+                "--multi-platform",
+                "--modified",
+                "--format",
+                "--help"
+            ]
+        );
+        let untranslated = find(&options, "--untranslated");
+        assert_eq!(untranslated.argument.as_deref(), Some("<LANGUAGE>"));
+        assert_eq!(
+            untranslated.description,
+            "Only list pages that have not been translated to LANGUAGE"
+        );
+        assert_eq!(find(&options, "--format").description, "Output format");
+    }
+
+    #[test]
+    fn descriptions() {
+        assert_eq!(
+            help_description(LS_HELP).as_deref(),
+            Some("List information about the FILEs (the current directory by default).")
+        );
+        assert_eq!(
+            help_description(CLAP_HELP).as_deref(),
+            Some("List pages (on all platforms by default).")
+        );
+        assert_eq!(
+            help_description("usage: foo [-v]\n       foo --help\n\nfoo does things\n").as_deref(),
+            Some("Foo does things.")
+        );
+        assert_eq!(help_description("Usage: foo\n"), None);
+    }
+
+    #[test]
+    fn scaffolded_page() {
+        let options = parse_options(LS_HELP);
+        let page = page(
+            "ls",
+            help_description(LS_HELP),
+            &options,
+            "https://example.com",
+            "en",
+        );
+
+        assert_eq!(page.examples.len(), MAX_EXAMPLES);
+        assert_eq!(page.examples[0].command.to_string(), "ls {{[-a|--all]}}");
+        assert_eq!(
+            page.examples[4].command.to_string(),
+            "ls {{--block-size}} {{size}}"
+        );
+        assert!(page.examples[4]
+            .description
+            .starts_with("With -l, scale sizes"));
+    }
+
+    #[test]
+    fn roff() {
+        let text = roff_to_text(LS_ROFF);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(lines.contains(&"NAME"));
+        assert!(lines.contains(&"       ls - list directory contents"));
+        assert!(lines.contains(&"       --block-size=SIZE"));
+        assert!(lines.contains(&"              with -l, scale sizes by SIZE when printing them;"));
+        assert!(lines.contains(&"   Exit status:"));
+        assert!(!text.contains('\\'));
+    }
+
+    #[test]
+    fn sections() {
+        assert_eq!(
+            man_section(LS_MAN, "NAME"),
+            Some(vec!["       ls - list directory contents", ""])
+        );
+        assert_eq!(
+            man_section(LS_MAN, "SYNOPSIS"),
+            Some(vec!["       ls [OPTION]... [FILE]...", ""])
+        );
+        assert_eq!(man_section(LS_MAN, "OPTIONS"), None);
+        // Subsections are a part of their section.
+        assert!(man_section(LS_MAN, "DESCRIPTION")
+            .unwrap()
+            .contains(&"   Exit status:"));
+    }
+
+    #[test]
+    fn man_descriptions() {
+        assert_eq!(
+            man_description(LS_MAN).as_deref(),
+            Some("List directory contents.")
+        );
+        assert_eq!(
+            man_description(&roff_to_text(LS_ROFF)).as_deref(),
+            Some("List directory contents.")
+        );
+        assert_eq!(
+            man_description("DESCRIPTION\n       Frobnicate files. With no FILE, read stdin.\n")
+                .as_deref(),
+            Some("Frobnicate files.")
+        );
+    }
+
+    #[test]
+    fn formatted_man_options() {
+        let options = man_options(LS_MAN);
+
+        assert_eq!(options[0].short.as_deref(), Some("-a"));
+        assert_eq!(options[0].long.as_deref(), Some("--all"));
+        assert_eq!(
+            options[0].description,
+            "do not ignore entries starting with ."
+        );
+
+        let block_size = find(&options, "--block-size");
+        assert_eq!(block_size.argument.as_deref(), Some("SIZE"));
+        assert_eq!(
+            block_size.description,
+            "with -l, scale sizes by SIZE when printing them; e.g., '--block-size=M'; see SIZE format below"
+        );
+
+        // The description is on the same line as a short option.
+        let ctime = options
+            .iter()
+            .find(|option| option.short.as_deref() == Some("-c"))
+            .unwrap();
+        assert!(ctime
+            .description
+            .starts_with("with -lt: sort by, and show, ctime"));
+        assert!(ctime
+            .description
+            .ends_with("otherwise: sort by ctime, newest first"));
+
+        assert_eq!(
+            find(&options, "--help").description,
+            "display this help and exit"
+        );
+        assert_eq!(
+            find(&options, "--color").argument.as_deref(),
+            Some("[=WHEN]")
+        );
+        // Exit statuses are not options.
+        assert!(options
+            .iter()
+            .all(|option| option.short.as_deref() != Some("-0")));
+    }
+
+    #[test]
+    fn roff_man_options() {
+        let options = man_options(&roff_to_text(LS_ROFF));
+
+        assert_eq!(options[0].long.as_deref(), Some("--all"));
+        assert_eq!(options.last().unwrap().long.as_deref(), Some("--version"));
+        assert_eq!(
+            find(&options, "--block-size").description,
+            "with -l, scale sizes by SIZE when printing them; e.g., '--block-size=M'; see SIZE format below"
+        );
+        assert_eq!(
+            find(&options, "--classify").argument.as_deref(),
+            Some("[=WHEN]")
+        );
+    }
+}
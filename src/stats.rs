@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::util::list_pages;
+
+/// Translation coverage of a single platform.
+#[derive(Serialize)]
+pub struct PlatformStats {
+    pub platform: String,
+    pub english: usize,
+    pub translated: usize,
+    pub percentage: f64,
+    /// English pages without a translation, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing: Option<Vec<String>>,
+}
+
+/// Translation coverage of a language, per platform and in total.
+#[derive(Serialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub platforms: Vec<PlatformStats>,
+    pub english: usize,
+    pub translated: usize,
+    pub percentage: f64,
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(translated: usize, english: usize) -> f64 {
+    if english == 0 {
+        0.0
+    } else {
+        // Round to one decimal place.
+        (translated as f64 / english as f64 * 1000.0).round() / 10.0
+    }
+}
+
+/// Groups the pages in `language` by platform.
+fn pages_by_platform(
+    repo_path: &Path,
+    language: &str,
+) -> Result<BTreeMap<String, BTreeSet<String>>> {
+    let mut map: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for (platform, page) in list_pages(repo_path, language)? {
+        map.entry(platform).or_default().insert(page);
+    }
+
+    Ok(map)
+}
+
+/// Computes translation coverage of each language in `languages`.
+pub fn stats(
+    repo_path: &Path,
+    languages: &[&str],
+    list_missing: bool,
+) -> Result<Vec<LanguageStats>> {
+    let english = pages_by_platform(repo_path, "en")?;
+    let mut stats = vec![];
+
+    for &language in languages {
+        let translations = pages_by_platform(repo_path, language)?;
+        let mut platforms = vec![];
+
+        for (platform, en_pages) in &english {
+            let tr_pages = translations.get(platform);
+            let is_translated = |page: &&String| tr_pages.is_some_and(|p| p.contains(*page));
+
+            let translated = en_pages.iter().filter(is_translated).count();
+            let missing = list_missing.then(|| {
+                en_pages
+                    .iter()
+                    .filter(|page| !is_translated(page))
+                    .cloned()
+                    .collect()
+            });
+
+            platforms.push(PlatformStats {
+                platform: platform.clone(),
+                english: en_pages.len(),
+                translated,
+                percentage: percentage(translated, en_pages.len()),
+                missing,
+            });
+        }
+
+        let english_total = platforms.iter().map(|p| p.english).sum();
+        let translated_total = platforms.iter().map(|p| p.translated).sum();
+        stats.push(LanguageStats {
+            language: language.to_string(),
+            platforms,
+            english: english_total,
+            translated: translated_total,
+            percentage: percentage(translated_total, english_total),
+        });
+    }
+
+    Ok(stats)
+}
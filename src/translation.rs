@@ -0,0 +1,407 @@
+use std::fmt::{self, Display};
+
+use crate::consts::MORE_INFORMATION;
+use crate::error::Result;
+use crate::page::{Command, Page, Token};
+
+/// Returns the text of a command line without placeholders, because they are usually translated.
+pub fn command_skeleton(command: &Command) -> String {
+    let text: String = command
+        .0
+        .iter()
+        .map(|token| match token {
+            Token::Text(s) => s.as_str(),
+            Token::Placeholder(_) => " ",
+        })
+        .collect();
+
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Returns the placeholders of a command line as a sorted multiset.
+///
+/// The contents of most placeholders are translated, so only option placeholders
+/// (e.g. `{{[-f|--force]}}`) are compared as-is and any other placeholder is `None`.
+fn placeholder_multiset(command: &Command) -> Vec<Option<&str>> {
+    let mut placeholders: Vec<_> = command
+        .0
+        .iter()
+        .filter_map(|token| match token {
+            Token::Placeholder(s) if s.starts_with('-') || s.starts_with("[-") => {
+                Some(Some(s.as_str()))
+            }
+            Token::Placeholder(_) => Some(None),
+            Token::Text(_) => None,
+        })
+        .collect();
+
+    placeholders.sort_unstable();
+    placeholders
+}
+
+/// A way in which a translation differs from the English page.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// The number of examples in the English page and in the translation.
+    ExampleCount(usize, usize),
+    /// 1-based numbers of examples whose command lines (ignoring placeholders) differ.
+    Commands(Vec<usize>),
+    /// 1-based numbers of examples with a different number of placeholders or different options
+    /// in placeholders.
+    Placeholders(Vec<usize>),
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |numbers: &[usize]| {
+            numbers
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        match self {
+            Self::ExampleCount(en, tr) => write!(f, "{tr} examples (English: {en})"),
+            Self::Commands(numbers) => write!(f, "commands differ in examples {}", join(numbers)),
+            Self::Placeholders(numbers) => {
+                write!(f, "placeholders differ in examples {}", join(numbers))
+            }
+        }
+    }
+}
+
+/// Compares a translation with its English page, example by example.
+pub fn differences(english: &Page, translation: &Page) -> Vec<Difference> {
+    let mut differences = vec![];
+
+    if english.examples.len() != translation.examples.len() {
+        differences.push(Difference::ExampleCount(
+            english.examples.len(),
+            translation.examples.len(),
+        ));
+    }
+
+    let mut commands = vec![];
+    let mut placeholders = vec![];
+    for (i, (en, tr)) in english
+        .examples
+        .iter()
+        .zip(&translation.examples)
+        .enumerate()
+    {
+        if command_skeleton(&en.command) != command_skeleton(&tr.command) {
+            commands.push(i + 1);
+        }
+        if placeholder_multiset(&en.command) != placeholder_multiset(&tr.command) {
+            placeholders.push(i + 1);
+        }
+    }
+
+    if !commands.is_empty() {
+        differences.push(Difference::Commands(commands));
+    }
+    if !placeholders.is_empty() {
+        differences.push(Difference::Placeholders(placeholders));
+    }
+
+    differences
+}
+
+/// A row in the example-by-example alignment of an English page and its translation.
+/// The numbers are 0-based indices of examples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Examples with the same command lines (ignoring placeholder contents).
+    Same(usize, usize),
+    /// Examples at the same position whose command lines differ.
+    Changed(usize, usize),
+    /// An example which is missing in the translation.
+    English(usize),
+    /// An example which is missing in the English page.
+    Translation(usize),
+}
+
+/// Aligns the examples of two pages by their command lines using the longest common subsequence,
+/// pairing up the remaining examples in between by position.
+pub fn align(english: &Page, translation: &Page) -> Vec<Alignment> {
+    fn key(command: &Command) -> (String, Vec<Option<&str>>) {
+        (command_skeleton(command), placeholder_multiset(command))
+    }
+    let en: Vec<_> = english.examples.iter().map(|ex| key(&ex.command)).collect();
+    let tr: Vec<_> = translation
+        .examples
+        .iter()
+        .map(|ex| key(&ex.command))
+        .collect();
+
+    // lcs[i][j] is the length of the LCS of en[i..] and tr[j..].
+    let mut lcs = vec![vec![0; tr.len() + 1]; en.len() + 1];
+    for i in (0..en.len()).rev() {
+        for j in (0..tr.len()).rev() {
+            lcs[i][j] = if en[i] == tr[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < en.len() && j < tr.len() {
+        if en[i] == tr[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches.push((en.len(), tr.len()));
+
+    let mut rows = vec![];
+    let (mut i, mut j) = (0, 0);
+    for (next_i, next_j) in matches {
+        while i < next_i && j < next_j {
+            rows.push(Alignment::Changed(i, j));
+            i += 1;
+            j += 1;
+        }
+        rows.extend((i..next_i).map(Alignment::English));
+        rows.extend((j..next_j).map(Alignment::Translation));
+
+        if next_i < en.len() {
+            rows.push(Alignment::Same(next_i, next_j));
+        }
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+
+    rows
+}
+
+/// Creates a translation skeleton from an English page. Commands and placeholders are kept as-is,
+/// while descriptions are prefixed with `marker` to show that they have to be translated.
+pub fn scaffold(english: &Page, language: &str, marker: &str) -> Page {
+    let mut translation = english.clone();
+
+    for line in &mut translation.description {
+        *line = format!("{marker} {line}");
+    }
+    if let Some(info) = &mut translation.more_information {
+        info.label = MORE_INFORMATION[language].to_string();
+    }
+    for example in &mut translation.examples {
+        example.description = format!("{marker} {}", example.description);
+    }
+
+    translation
+}
+
+/// The reason a line of a translation is considered untranslated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Leftover {
+    /// The line is identical to the corresponding English line.
+    Identical,
+    /// The line still contains the marker added by `scaffold`.
+    Marker,
+}
+
+impl Display for Leftover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identical => write!(f, "identical to the English page"),
+            Self::Marker => write!(f, "contains the untranslated marker"),
+        }
+    }
+}
+
+/// Finds lines of a translation that have not been translated. Returns 1-based line numbers.
+/// Titles and command lines are never reported as identical.
+pub fn untranslated_lines(
+    english: Option<&Page>,
+    translation: &str,
+    marker: &str,
+) -> Result<Vec<(usize, Leftover)>> {
+    let parsed = Page::parse(translation)?;
+
+    // Translation example index => the corresponding English example description.
+    let mut en_examples = vec![None; parsed.examples.len()];
+    let mut en_description = vec![];
+    if let Some(english) = english {
+        for row in align(english, &parsed) {
+            if let Alignment::Same(i, j) | Alignment::Changed(i, j) = row {
+                en_examples[j] = Some(english.examples[i].description.as_str());
+            }
+        }
+
+        en_description.clone_from(&english.description);
+        if let Some(info) = &english.more_information {
+            en_description.push(format!("{}: <{}>.", info.label, info.url));
+        }
+    }
+
+    let mut leftovers = vec![];
+    let mut example = 0;
+    for (i, line) in translation.lines().enumerate() {
+        let line = line.trim_end();
+
+        let identical = if let Some(text) = line.strip_prefix('>') {
+            let text = text.trim_start();
+            !text.is_empty() && en_description.iter().any(|en| en == text)
+        } else if let Some(text) = line.strip_prefix('-') {
+            example += 1;
+            en_examples[example - 1] == Some(text.trim_start())
+        } else {
+            // Titles and commands are not translated.
+            continue;
+        };
+
+        if line.contains(marker) {
+            leftovers.push((i + 1, Leftover::Marker));
+        } else if identical {
+            leftovers.push((i + 1, Leftover::Identical));
+        }
+    }
+
+    Ok(leftovers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::Example;
+
+    fn page(commands: &[&str]) -> Page {
+        Page {
+            title: "rm".to_string(),
+            description: vec!["Remove files.".to_string()],
+            more_information: None,
+            examples: commands
+                .iter()
+                .map(|command| Example {
+                    description: "Example:".to_string(),
+                    command: Command::parse(command),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn translated_placeholders() {
+        assert_eq!(
+            differences(
+                &page(&[
+                    "rm {{path/to/file}}",
+                    "rm {{[-r|--recursive]}} {{path/to/directory}}"
+                ]),
+                &page(&[
+                    "rm {{ruta/al/archivo}}",
+                    "rm {{[-r|--recursive]}} {{ruta/al/directorio}}"
+                ])
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn different_placeholders() {
+        let english = page(&["rm {{[-r|--recursive]}} {{path}}", "rm {{path}}"]);
+
+        assert_eq!(
+            differences(
+                &english,
+                &page(&["rm {{[-f|--force]}} {{ruta}}", "rm {{ruta}}"])
+            ),
+            [Difference::Placeholders(vec![1])]
+        );
+        // Both differences are reported for the same example.
+        assert_eq!(
+            differences(
+                &english,
+                &page(&["rm {{[-r|--recursive]}} {{ruta}}", "rm -f"])
+            ),
+            [
+                Difference::Commands(vec![2]),
+                Difference::Placeholders(vec![2])
+            ]
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        use Alignment::{Changed, English, Same, Translation};
+
+        assert_eq!(
+            align(
+                &page(&["ls", "ls -a", "ls -l"]),
+                &page(&["ls", "ls -l", "cp"])
+            ),
+            [Same(0, 0), English(1), Same(2, 1), Translation(2)]
+        );
+        // Placeholder contents do not matter, but a changed command line is paired by position.
+        assert_eq!(
+            align(
+                &page(&["ls {{path}}", "ls -a"]),
+                &page(&["ls {{ruta}}", "ls -R"])
+            ),
+            [Same(0, 0), Changed(1, 1)]
+        );
+    }
+
+    const ENGLISH: &str = "# ls
+
+> List files.
+> More information: <https://example.com>.
+
+- List files:
+
+`ls`
+
+- List all files:
+
+`ls -a`
+";
+
+    const TRANSLATION: &str = "# ls
+
+> Liste les fichiers.
+> More information: <https://example.com>.
+
+- TODO: List files:
+
+`ls`
+
+- List all files:
+
+`ls -a`
+";
+
+    #[test]
+    fn untranslated() {
+        let english = Page::parse(ENGLISH).unwrap();
+
+        assert_eq!(
+            untranslated_lines(Some(&english), TRANSLATION, "TODO:").unwrap(),
+            [
+                (4, Leftover::Identical),
+                (6, Leftover::Marker),
+                (10, Leftover::Identical)
+            ]
+        );
+        // Without the English page, only the marker can be detected.
+        assert_eq!(
+            untranslated_lines(None, TRANSLATION, "TODO:").unwrap(),
+            [(6, Leftover::Marker)]
+        );
+        // The title and command lines are never reported.
+        let lines: Vec<usize> = untranslated_lines(Some(&english), ENGLISH, "TODO:")
+            .unwrap()
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect();
+        assert_eq!(lines, [3, 4, 6, 10]);
+    }
+}
@@ -1,11 +1,14 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
 use std::ffi::OsStr;
-use std::io::{self, Write};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::str;
 
+use once_cell::sync::OnceCell;
 use yansi::{Color, Paint};
 
 use crate::args::Platform;
@@ -120,8 +123,8 @@ pub fn is_success(cmd: &mut Command) -> Result<bool> {
     }
 }
 
-/// Spawns a child process and returns its stdout as a `String`, with the trailing newline stripped.
-pub fn get_output(cmd: &mut Command) -> Result<String> {
+/// Spawns a child process and returns its stdout, printing it only if `print` is `true`.
+fn capture_output(cmd: &mut Command, print: bool) -> Result<String> {
     write!(
         io::stderr(),
         "{} {} {} ... ",
@@ -140,11 +143,15 @@ pub fn get_output(cmd: &mut Command) -> Result<String> {
         let output_str = str::from_utf8(&output.stdout).unwrap();
         let output_str = output_str.strip_suffix('\n').unwrap_or(output_str);
 
-        writeln!(
-            io::stdout(),
-            "{}",
-            Paint::new(&output_str).fg(Color::Green).bold()
-        )?;
+        if print {
+            writeln!(
+                io::stdout(),
+                "{}",
+                Paint::new(&output_str).fg(Color::Green).bold()
+            )?;
+        } else {
+            writeln!(io::stderr(), "{}", Paint::new("OK").fg(Color::Green).bold())?;
+        }
         Ok(output_str.to_string())
     } else {
         writeln!(
@@ -156,20 +163,160 @@ pub fn get_output(cmd: &mut Command) -> Result<String> {
     }
 }
 
-/// Constructs a `PathBuf` to a page.
-pub fn page_path(repo_path: &Path, platform: &Platform, language: &str, page: &str) -> PathBuf {
-    let lang_dir = if language == "en" {
-        "pages".to_string()
+/// Spawns a child process and returns its stdout as a `String`, with the trailing newline stripped.
+pub fn get_output(cmd: &mut Command) -> Result<String> {
+    capture_output(cmd, true)
+}
+
+/// Like `get_output`, but does not print the output. Used for output meant to be parsed.
+pub fn get_output_quiet(cmd: &mut Command) -> Result<String> {
+    capture_output(cmd, false)
+}
+
+/// Constructs a `PathBuf` to the directory containing pages in `language`.
+pub fn language_dir(repo_path: &Path, language: &str) -> PathBuf {
+    if language == "en" {
+        repo_path.join("pages")
     } else {
-        format!("pages.{language}")
-    };
+        repo_path.join(format!("pages.{language}"))
+    }
+}
 
-    PathBuf::from(repo_path)
-        .join(lang_dir)
+/// Constructs a `PathBuf` to a page.
+pub fn page_path(repo_path: &Path, platform: &Platform, language: &str, page: &str) -> PathBuf {
+    language_dir(repo_path, language)
         .join(platform.to_string())
         .join(page.replace(' ', "-") + ".md")
 }
 
+/// Makes `path` absolute by joining it to the current directory.
+/// Symlinks are not resolved, and the path does not have to exist.
+pub fn absolute(path: &Path) -> Result<PathBuf> {
+    Ok(env::current_dir()?.join(path))
+}
+
+/// Returns the names of all platform directories and pages in `language`, sorted.
+/// Returns an empty list if there are no pages in `language`.
+pub fn list_pages(repo_path: &Path, language: &str) -> Result<Vec<(String, String)>> {
+    let lang_dir = language_dir(repo_path, language);
+    let mut pages = vec![];
+
+    if !lang_dir.is_dir() {
+        return Ok(pages);
+    }
+
+    for platform in fs::read_dir(lang_dir)? {
+        let platform = platform?;
+        if !platform.file_type()?.is_dir() {
+            continue;
+        }
+        let platform_name = platform.file_name().to_string_lossy().into_owned();
+
+        for page in fs::read_dir(platform.path())? {
+            let path = page?.path();
+            if path.extension() == Some(OsStr::new("md")) {
+                let page_name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                pages.push((platform_name.clone(), page_name));
+            }
+        }
+    }
+
+    pages.sort();
+    Ok(pages)
+}
+
+/// Returns the languages of all page directories in the repository, sorted.
+pub fn list_languages(repo_path: &Path) -> Result<Vec<String>> {
+    let mut languages = vec![];
+
+    for entry in fs::read_dir(repo_path)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name == "pages" {
+            languages.push("en".to_string());
+        } else if let Some(language) = name.strip_prefix("pages.") {
+            languages.push(language.to_string());
+        }
+    }
+
+    languages.sort();
+    Ok(languages)
+}
+
+/// A copy of a page in some language and on some platform.
+pub struct PageCopy {
+    pub language: String,
+    pub platform: String,
+    pub path: PathBuf,
+}
+
+/// Finds every language's copy of `page`, on `platform` or on all platforms.
+pub fn page_copies(
+    repo_path: &Path,
+    platform: Option<&Platform>,
+    page: &str,
+) -> Result<Vec<PageCopy>> {
+    let mut copies = vec![];
+
+    for language in list_languages(repo_path)? {
+        for (page_platform, name) in list_pages(repo_path, &language)? {
+            if name != page || platform.is_some_and(|p| p.to_string() != page_platform) {
+                continue;
+            }
+            let path = language_dir(repo_path, &language)
+                .join(&page_platform)
+                .join(format!("{name}.md"));
+
+            copies.push(PageCopy {
+                language: language.clone(),
+                platform: page_platform,
+                path,
+            });
+        }
+    }
+
+    Ok(copies)
+}
+
+/// Returns paths to all pages in all languages.
+pub fn all_pages(repo_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+
+    for language in list_languages(repo_path)? {
+        let lang_dir = language_dir(repo_path, &language);
+        for (platform, page) in list_pages(repo_path, &language)? {
+            paths.push(lang_dir.join(platform).join(format!("{page}.md")));
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Returns the timestamp and date of the last commit touching each file in `paths`
+/// (relative to the repository), using a single `git log` invocation.
+pub fn last_commit_dates(paths: &[PathBuf]) -> Result<HashMap<PathBuf, (u64, String)>> {
+    let mut cmd = cmd!("git", "log", "--format=%x00%ct %cs", "--name-only", "--");
+    cmd.args(paths);
+    let log = get_output_quiet(&mut cmd)?;
+
+    let mut dates = HashMap::new();
+    for commit in log.split('\0').skip(1) {
+        let mut lines = commit.lines();
+        let Some((timestamp, date)) = lines.next().and_then(|l| l.split_once(' ')) else {
+            continue;
+        };
+        let timestamp = timestamp.parse().unwrap_or_default();
+
+        for file in lines.filter(|l| !l.is_empty()) {
+            // The log starts with the newest commit, so keep the first date seen.
+            dates
+                .entry(PathBuf::from(file))
+                .or_insert_with(|| (timestamp, date.to_string()));
+        }
+    }
+
+    Ok(dates)
+}
+
 /// Constructs a `PathBuf` to a page, and returns an error if it does not exist.
 pub fn existing_page_path(
     repo_path: &Path,
@@ -182,18 +329,333 @@ pub fn existing_page_path(
     if path.is_file() {
         Ok(path)
     } else {
-        Err(Error::Msg(format!(
-            "{language}/{platform}/{page}: page does not exist"
-        )))
+        Err(page_not_found(repo_path, Some(platform), language, page))
+    }
+}
+
+/// Computes the edit distance between `a` and `b`, counting adjacent transpositions as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // d[i][j] is the distance between a[..i] and b[..j].
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+/// Returns up to five suggestions (as arguments to `tlt`) for a page that does not exist.
+fn suggestions(
+    repo_path: &Path,
+    platform: Option<&Platform>,
+    language: &str,
+    page: &str,
+) -> Result<Vec<String>> {
+    let page = page.replace(' ', "-");
+    let mut suggestions = vec![];
+
+    // The same page on other platforms.
+    if let Some(platform) = platform {
+        for other in Platform::all(repo_path)? {
+            if &other != platform && page_path(repo_path, &other, language, &page).is_file() {
+                suggestions.push(format!("--platform {other} {page}"));
+            }
+        }
+    }
+
+    // The same page in other languages, if it does not exist in this one.
+    let pages = list_pages(repo_path, language)?;
+    if !pages.iter().any(|(_, name)| *name == page) {
+        for other in list_languages(repo_path)? {
+            if other != language
+                && list_pages(repo_path, &other)?
+                    .iter()
+                    .any(|(_, name)| *name == page)
+            {
+                suggestions.push(format!("--language {other} {page}"));
+            }
+        }
+    }
+
+    // Similar page names on the same platform(s).
+    let strip_separators = |s: &str| s.replace(['-', '_'], "");
+    let max_distance = (page.chars().count() / 3).clamp(1, 3);
+    let mut similar: Vec<(usize, &str)> = pages
+        .iter()
+        .filter(|(p, _)| platform.map_or(true, |platform| *p == platform.to_string()))
+        .filter_map(|(_, name)| {
+            let rank = if strip_separators(name) == strip_separators(&page) {
+                0
+            } else if edit_distance(name, &page) <= max_distance {
+                edit_distance(name, &page)
+            } else if name.starts_with(&page) {
+                max_distance + 1
+            } else {
+                return None;
+            };
+            Some((rank, name.as_str()))
+        })
+        .collect();
+    similar.sort_unstable();
+    similar.dedup_by_key(|(_, name)| *name);
+    suggestions.extend(similar.into_iter().map(|(_, name)| name.to_string()));
+
+    suggestions.truncate(5);
+    Ok(suggestions)
+}
+
+/// Returns an error for a page that does not exist, including suggestions for similar pages.
+pub fn page_not_found(
+    repo_path: &Path,
+    platform: Option<&Platform>,
+    language: &str,
+    page: &str,
+) -> Error {
+    let location = match platform {
+        Some(platform) => format!("{language}/{platform}/{page}: page does not exist"),
+        None => format!("{language}/*/{page}: page does not exist on any platform"),
+    };
+
+    match suggestions(repo_path, platform, language, page) {
+        Ok(suggestions) if !suggestions.is_empty() => Error::Msg(format!(
+            "{location}. Did you mean:\n  tlt {}",
+            suggestions.join("\n  tlt ")
+        )),
+        _ => Error::Msg(location),
+    }
+}
+
+/// Returns the name of the tldr platform matching the host system.
+fn host_platform() -> Option<&'static str> {
+    match env::consts::OS {
+        "linux" => Some("linux"),
+        "macos" => Some("osx"),
+        "windows" => Some("windows"),
+        "android" => Some("android"),
+        "freebsd" => Some("freebsd"),
+        "openbsd" => Some("openbsd"),
+        "netbsd" => Some("netbsd"),
+        "solaris" | "illumos" => Some("sunos"),
+        _ => None,
+    }
+}
+
+/// Returns all platforms in the order in which tldr clients search them:
+/// `common`, the host platform, and then the others.
+pub fn platform_precedence(repo_path: &Path) -> Result<Vec<Platform>> {
+    let mut platforms = Platform::all(repo_path)?;
+    let rank = |p: &Platform| {
+        let name = p.to_string();
+        if name == "common" {
+            0
+        } else if Some(name.as_str()) == host_platform() {
+            1
+        } else {
+            2
+        }
+    };
+    // The sort is stable, so the other platforms stay sorted by name.
+    platforms.sort_by_key(rank);
+
+    Ok(platforms)
+}
+
+/// Asks a yes/no question. Anything other than `y` or `yes`, including end of input, means no.
+pub fn confirm(question: &str) -> Result<bool> {
+    write!(io::stdout(), "{question} [y/N] ")?;
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks the user to choose one of `platforms`. The first one is the default.
+fn choose_platform(page: &str, platforms: Vec<Platform>) -> Result<Platform> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "page '{page}' exists on multiple platforms:")?;
+    for (i, platform) in platforms.iter().enumerate() {
+        writeln!(stdout, "  {}) {platform}", i + 1)?;
+    }
+    write!(stdout, "Choose a platform [1]: ")?;
+    stdout.flush()?;
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? == 0 {
+        return Err(Error::Msg("no platform chosen".to_string()));
+    }
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Ok(platforms.into_iter().next().unwrap());
+    }
+
+    answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| platforms.into_iter().nth(n.checked_sub(1)?))
+        .ok_or_else(|| Error::Msg(format!("invalid choice '{answer}'")))
+}
+
+/// Finds a page on `platform`. If `platform` is `None`, all platforms are searched
+/// (see `platform_precedence`). If the page exists on multiple platforms,
+/// the user is asked to choose one, or an error is returned if stdin is not a terminal.
+pub fn find_page(
+    repo_path: &Path,
+    platform: Option<&Platform>,
+    language: &str,
+    page: &str,
+) -> Result<(Platform, PathBuf)> {
+    if let Some(platform) = platform {
+        let path = existing_page_path(repo_path, platform, language, page)?;
+        return Ok((platform.clone(), path));
+    }
+
+    let mut found: Vec<Platform> = platform_precedence(repo_path)?
+        .into_iter()
+        .filter(|platform| page_path(repo_path, platform, language, page).is_file())
+        .collect();
+
+    let platform = match found.len() {
+        0 => return Err(page_not_found(repo_path, None, language, page)),
+        1 => found.remove(0),
+        _ if io::stdin().is_terminal() => choose_platform(page, found)?,
+        _ => {
+            let found: Vec<String> = found.iter().map(ToString::to_string).collect();
+            return Err(Error::Msg(format!(
+                "page '{page}' exists on multiple platforms ({}), specify one using '--platform'",
+                found.join(", ")
+            )));
+        }
+    };
+
+    let path = page_path(repo_path, &platform, language, page);
+    Ok((platform, path))
+}
+
+/// Returns the language of the page at `path`, based on the name of its language directory.
+pub fn page_language(path: &Path) -> String {
+    let lang_dir = path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_name)
+        .map(OsStr::to_string_lossy)
+        .unwrap_or_default();
+
+    match lang_dir.strip_prefix("pages.") {
+        Some(language) => language.to_string(),
+        None => "en".to_string(),
     }
 }
 
-/// Edits `path` using `$EDITOR` or `nano`.
+/// Returns paths to pages added or modified on the current branch (including uncommitted changes).
+pub fn changed_pages(repo_path: &Path) -> Result<Vec<PathBuf>> {
+    let modified = get_output_quiet(&mut cmd!(
+        "git",
+        "diff",
+        "--name-only",
+        "--diff-filter=d",
+        "--merge-base",
+        "main"
+    ))?;
+    let untracked = get_output_quiet(&mut cmd!(
+        "git",
+        "ls-files",
+        "--others",
+        "--exclude-standard"
+    ))?;
+
+    let mut pages: Vec<PathBuf> = modified
+        .lines()
+        .chain(untracked.lines())
+        .filter(|path| {
+            let components: Vec<&str> = path.split('/').collect();
+            components.len() == 3
+                && components[0].starts_with("pages")
+                && Path::new(path).extension() == Some(OsStr::new("md"))
+        })
+        .map(|path| repo_path.join(path))
+        .collect();
+    pages.sort();
+    pages.dedup();
+
+    Ok(pages)
+}
+
+/// Returns the copies of a page that are not tracked by git.
+pub fn untracked_copies<'a>(repo_path: &Path, copies: &'a [PageCopy]) -> Result<Vec<&'a PageCopy>> {
+    let mut cmd = cmd!("git", "ls-files", "--full-name", "--");
+    cmd.args(copies.iter().map(|copy| &copy.path));
+    let tracked: Vec<PathBuf> = get_output_quiet(&mut cmd)?
+        .lines()
+        .map(|path| repo_path.join(path))
+        .collect();
+
+    Ok(copies
+        .iter()
+        .filter(|copy| !tracked.contains(&copy.path))
+        .collect())
+}
+
+/// Returns the copies of a page with uncommitted changes.
+pub fn modified_copies<'a>(repo_path: &Path, copies: &'a [PageCopy]) -> Result<Vec<&'a PageCopy>> {
+    let mut cmd = cmd!("git", "diff", "--name-only", "HEAD", "--");
+    cmd.args(copies.iter().map(|copy| &copy.path));
+    let modified: Vec<PathBuf> = get_output_quiet(&mut cmd)?
+        .lines()
+        .map(|path| repo_path.join(path))
+        .collect();
+
+    Ok(copies
+        .iter()
+        .filter(|copy| modified.contains(&copy.path))
+        .collect())
+}
+
+/// The editor set in the config file, which may include arguments, e.g. `code --wait`.
+static CONFIG_EDITOR: OnceCell<String> = OnceCell::new();
+
+/// Makes `edit_page` use `editor` instead of `$EDITOR`.
+pub fn set_editor(editor: &str) {
+    CONFIG_EDITOR.set(editor.to_string()).ok();
+}
+
+/// Edits `path` using the editor from the config file, `$EDITOR` or `nano`.
+/// `$EDITOR` is a single program, so its path may contain spaces.
 pub fn edit_page(path: &Path) -> Result<()> {
-    spawn(&mut cmd!(
-        env::var("EDITOR").unwrap_or("nano".to_string()),
-        path
-    ))
+    let mut cmd = if let Some(editor) = CONFIG_EDITOR.get() {
+        let mut words = editor.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| Error::Msg("the editor must not be empty".to_string()))?;
+        let mut cmd = cmd!(program);
+        cmd.args(words);
+        cmd
+    } else {
+        cmd!(env::var_os("EDITOR")
+            .filter(|editor| !editor.is_empty())
+            .unwrap_or("nano".into()))
+    };
+
+    spawn(cmd.arg(path))
 }
 
 /// Open `url` in the user's default browser.
@@ -207,3 +669,63 @@ pub fn browser(url: &str) -> Result<()> {
     #[cfg(target_os = "windows")]
     return spawn(&mut cmd!("explorer", url));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a clone of tldr with the English `pages` on `common` in the temporary directory.
+    fn repo(name: &str, pages: &[&str]) -> PathBuf {
+        let path = env::temp_dir().join(format!("tlt-{}-{name}", std::process::id()));
+        let dir = path.join("pages").join("common");
+        fs::create_dir_all(&dir).unwrap();
+        for page in pages {
+            fs::write(dir.join(format!("{page}.md")), "").unwrap();
+        }
+        path
+    }
+
+    fn suggest(repo_path: &Path, page: &str) -> Vec<String> {
+        let common = "common".parse().unwrap();
+        suggestions(repo_path, Some(&common), "en", page).unwrap()
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("git", "git"), 0);
+        assert_eq!(edit_distance("gti", "git"), 1);
+        assert_eq!(edit_distance("", "tar"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn similar_pages() {
+        let path = repo("similar", &["docker-compose", "git", "gzip"]);
+
+        assert_eq!(suggest(&path, "gti"), ["git"]);
+        assert_eq!(suggest(&path, "dockercompose"), ["docker-compose"]);
+        assert_eq!(suggest(&path, "docker_compose"), ["docker-compose"]);
+        assert_eq!(suggest(&path, "docker compose"), ["docker-compose"]);
+        assert_eq!(suggest(&path, "docker"), ["docker-compose"]);
+        assert!(suggest(&path, "tar").is_empty());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn at_most_five_suggestions() {
+        let pages = [
+            "git-add",
+            "git-bisect",
+            "git-clone",
+            "git-diff",
+            "git-log",
+            "git-push",
+        ];
+        let path = repo("five", &pages);
+
+        assert_eq!(suggest(&path, "git"), pages[..5]);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}